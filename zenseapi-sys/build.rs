@@ -0,0 +1,261 @@
+//! Locates libvzense_api and links it, unless the `runtime-loading` or `no-link` feature is on.
+//!
+//! The SDK is searched in this order:
+//!
+//! 1. `VZENSE_SDK_PATH`: the library, the directory containing it, or the root of the SDK
+//! 2. pkg-config, as `vzense_api`
+//! 3. directories in `LIBRARY_PATH`
+//! 4. standard install locations
+//!
+//! The SDK version is read from its headers, or pkg-config, and exported to the crate as
+//! `VZENSE_SDK_VERSION`. With the `bindgen` feature, the headers are also turned into
+//! `$OUT_DIR/bindings.rs`, even with `runtime-loading` or `no-link`.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LIBRARY_NAME: &str = "libvzense_api.so";
+const SDK_PATH_VAR: &str = "VZENSE_SDK_PATH";
+
+struct Sdk {
+    lib_dir: PathBuf,
+    include_dir: Option<PathBuf>,
+    version: Option<String>,
+}
+
+fn main() {
+    println!("cargo:rerun-if-env-changed={}", SDK_PATH_VAR);
+    println!("cargo:rerun-if-env-changed=LIBRARY_PATH");
+    println!("cargo:rerun-if-env-changed=DOCS_RS");
+
+    let runtime_loading = env::var_os("CARGO_FEATURE_RUNTIME_LOADING").is_some();
+    let no_link =
+        env::var_os("CARGO_FEATURE_NO_LINK").is_some() || env::var_os("DOCS_RS").is_some();
+    let bindgen = cfg!(feature = "bindgen");
+    if runtime_loading && !bindgen {
+        return;
+    }
+
+    let sdk = match find_sdk() {
+        Ok(sdk) => sdk,
+        Err(_) if (no_link || runtime_loading) && !bindgen => return,
+        Err(searched) => fail(&searched),
+    };
+    let version = sdk.version.clone().or_else(|| {
+        sdk.include_dir
+            .as_ref()
+            .and_then(|dir| version_from_headers(dir))
+    });
+    if let Some(version) = &version {
+        println!("cargo:rustc-env=VZENSE_SDK_VERSION={}", version);
+        println!("cargo:version={}", version);
+    }
+    println!("cargo:lib_dir={}", sdk.lib_dir.display());
+    if let Some(include_dir) = &sdk.include_dir {
+        println!("cargo:include={}", include_dir.display());
+    }
+    #[cfg(feature = "bindgen")]
+    generate_bindings(&sdk);
+    if no_link || runtime_loading {
+        return;
+    }
+
+    println!("cargo:rustc-link-search=native={}", sdk.lib_dir.display());
+    println!("cargo:rustc-link-lib=dylib=vzense_api");
+}
+
+/// Writes the bindings of the main header of the SDK to `$OUT_DIR/bindings.rs`.
+#[cfg(feature = "bindgen")]
+fn generate_bindings(sdk: &Sdk) {
+    let header = match &sdk.include_dir {
+        Some(include_dir) => include_dir.join("Vzense_api2.h"),
+        None => panic!(
+            "\n\nThe `bindgen` feature needs the headers of the Vzense SDK, which were not found \
+             next to {}.\n\n",
+            sdk.lib_dir.display()
+        ),
+    };
+    let bindings = bindgen::Builder::default()
+        .header(header.to_string_lossy())
+        .allowlist_type("Ps.*")
+        .allowlist_var("Ps.*")
+        .allowlist_function("Ps2_.*")
+        .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
+        .generate()
+        .unwrap_or_else(|e| {
+            panic!(
+                "failed to generate bindings from {}: {}",
+                header.display(),
+                e
+            )
+        });
+    let out_path = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("bindings.rs");
+    bindings
+        .write_to_file(&out_path)
+        .unwrap_or_else(|e| panic!("failed to write {}: {}", out_path.display(), e));
+}
+
+fn fail(searched: &[PathBuf]) -> ! {
+    let mut message = format!(
+        "\n\nCould not find the Vzense SDK ({}).\n\nSearched:\n",
+        LIBRARY_NAME
+    );
+    for dir in searched {
+        message.push_str(&format!("    {}\n", dir.display()));
+    }
+    message.push_str(&format!(
+        "\nTo fix this, either:\n\
+         \x20   * set {} to the library, its directory or the root of the SDK,\n\
+         \x20   * install a pkg-config file named vzense_api.pc,\n\
+         \x20   * enable the `runtime-loading` feature to load the SDK when the program runs, or\n\
+         \x20   * enable the `no-link` feature to build without the SDK, e.g. for documentation.\n\n",
+        SDK_PATH_VAR
+    ));
+    panic!("{}", message);
+}
+
+fn find_sdk() -> Result<Sdk, Vec<PathBuf>> {
+    let mut searched = Vec::new();
+
+    if let Some(path) = env::var_os(SDK_PATH_VAR).filter(|path| !path.is_empty()) {
+        let path = PathBuf::from(path);
+        let candidates = if path.is_file() {
+            path.parent().map(Path::to_path_buf).into_iter().collect()
+        } else {
+            vec![
+                path.clone(),
+                path.join("Lib"),
+                path.join("lib"),
+                path.join("lib64"),
+            ]
+        };
+        for dir in candidates {
+            if let Some(sdk) = sdk_in(&dir, &mut searched) {
+                return Ok(sdk);
+            }
+        }
+        // an explicit path which is wrong should not fall back to another SDK silently
+        return Err(searched);
+    }
+
+    if let Ok(library) = pkg_config::Config::new()
+        .cargo_metadata(false)
+        .probe("vzense_api")
+    {
+        for dir in &library.link_paths {
+            if dir.join(LIBRARY_NAME).is_file() {
+                return Ok(Sdk {
+                    lib_dir: dir.clone(),
+                    include_dir: library
+                        .include_paths
+                        .iter()
+                        .find(|dir| has_headers(dir))
+                        .cloned(),
+                    version: Some(library.version.clone()).filter(|v| !v.is_empty()),
+                });
+            }
+            searched.push(dir.clone());
+        }
+    }
+
+    if let Some(paths) = env::var_os("LIBRARY_PATH") {
+        for dir in env::split_paths(&paths) {
+            if let Some(sdk) = sdk_in(&dir, &mut searched) {
+                return Ok(sdk);
+            }
+        }
+    }
+
+    let mut standard = vec![
+        PathBuf::from("/usr/local/lib"),
+        PathBuf::from("/usr/lib"),
+        PathBuf::from("/opt/vzense/lib"),
+        PathBuf::from("/opt/Vzense/Lib"),
+    ];
+    if let (Ok(arch), Ok(os)) = (
+        env::var("CARGO_CFG_TARGET_ARCH"),
+        env::var("CARGO_CFG_TARGET_OS"),
+    ) {
+        standard.insert(2, PathBuf::from(format!("/usr/lib/{}-{}-gnu", arch, os)));
+    }
+    for dir in standard {
+        if let Some(sdk) = sdk_in(&dir, &mut searched) {
+            return Ok(sdk);
+        }
+    }
+    Err(searched)
+}
+
+/// The SDK whose library is in `lib_dir`, with headers in a sibling include directory.
+fn sdk_in(lib_dir: &Path, searched: &mut Vec<PathBuf>) -> Option<Sdk> {
+    searched.push(lib_dir.to_path_buf());
+    if !lib_dir.join(LIBRARY_NAME).is_file() {
+        return None;
+    }
+    let include_dir = lib_dir.parent().and_then(|root| {
+        [
+            root.join("Include"),
+            root.join("include"),
+            root.join("include").join("vzense"),
+        ]
+        .iter()
+        .find(|dir| has_headers(dir))
+        .cloned()
+    });
+    Some(Sdk {
+        lib_dir: lib_dir.to_path_buf(),
+        include_dir,
+        version: None,
+    })
+}
+
+fn headers(dir: &Path) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("Vzense_") && name.ends_with(".h"))
+        })
+        .collect()
+}
+
+fn has_headers(dir: &Path) -> bool {
+    !headers(dir).is_empty()
+}
+
+/// Finds a `#define ...VERSION... "x.y.z"` in the headers of the SDK.
+fn version_from_headers(include_dir: &Path) -> Option<String> {
+    for header in headers(include_dir) {
+        println!("cargo:rerun-if-changed={}", header.display());
+        let source = match fs::read_to_string(&header) {
+            Ok(source) => source,
+            Err(_) => continue,
+        };
+        for line in source.lines() {
+            let mut tokens = line.split_whitespace();
+            if tokens.next() != Some("#define") {
+                continue;
+            }
+            match tokens.next() {
+                Some(name) if name.to_ascii_uppercase().contains("VERSION") => {}
+                _ => continue,
+            }
+            let value = match tokens.next() {
+                Some(value) => value.trim_matches('"'),
+                None => continue,
+            };
+            if value.starts_with(|c: char| c.is_ascii_digit())
+                && value.chars().all(|c| c.is_ascii_digit() || c == '.')
+            {
+                return Some(value.to_string());
+            }
+        }
+    }
+    None
+}
@@ -1,4 +1,6 @@
+use std::convert::TryFrom;
 use std::ffi::CString;
+use std::fmt;
 use std::os::raw::c_int;
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
@@ -23,6 +25,22 @@ pub enum ZenseError {
     // Originally added
     RuntimeError,
     // Originally added
+    AlreadyInitialized,
+    // Originally added
+    InvalidSessionIndex,
+    // Originally added
+    InvalidFrameFormat,
+    // Originally added
+    InvalidFrameSize,
+    // Originally added
+    UnknownEnumValue,
+    // Originally added
+    SdkNotAvailable,
+    // Originally added
+    SymbolNotFound,
+    // Originally added
+    SessionStreaming,
+    // Originally added
     Unknown, // Originally added
 }
 
@@ -45,11 +63,128 @@ impl ZenseError {
             -14 => ZenseError::InvalidCameraType,
             -15 => ZenseError::InvalidParams,
             -255 => ZenseError::Others,
+            251 => ZenseError::UnknownEnumValue,
+            252 => ZenseError::SymbolNotFound,
+            253 => ZenseError::SdkNotAvailable,
             254 => ZenseError::RuntimeError,
             255 => ZenseError::FfiError,
             _ => ZenseError::Unknown,
         }
     }
+
+    /// The status code of the SDK which `from_int()` maps to this error, if there is one.
+    pub fn to_int(self) -> Option<c_int> {
+        match self {
+            ZenseError::NoDeviceConnected => Some(-1),
+            ZenseError::InvalidDeviceIndex => Some(-2),
+            ZenseError::DevicePointerIsNull => Some(-3),
+            ZenseError::InvalidFrameType => Some(-4),
+            ZenseError::FramePointerIsNull => Some(-5),
+            ZenseError::NoPropertyValueGet => Some(-6),
+            ZenseError::NoPropertyValueSet => Some(-7),
+            ZenseError::PropertyPointerIsNull => Some(-8),
+            ZenseError::PropertySizeNotEnough => Some(-9),
+            ZenseError::InvalidDepthRange => Some(-10),
+            ZenseError::ReadNextFrameError => Some(-11),
+            ZenseError::InputPointerIsNull => Some(-12),
+            ZenseError::CameraNotOpened => Some(-13),
+            ZenseError::InvalidCameraType => Some(-14),
+            ZenseError::InvalidParams => Some(-15),
+            ZenseError::Others => Some(-255),
+            ZenseError::UnknownEnumValue => Some(251),
+            ZenseError::SymbolNotFound => Some(252),
+            ZenseError::SdkNotAvailable => Some(253),
+            ZenseError::RuntimeError => Some(254),
+            ZenseError::FfiError => Some(255),
+            ZenseError::AlreadyInitialized
+            | ZenseError::InvalidSessionIndex
+            | ZenseError::InvalidFrameFormat
+            | ZenseError::InvalidFrameSize
+            | ZenseError::SessionStreaming
+            | ZenseError::Unknown => None,
+        }
+    }
+}
+
+impl fmt::Display for ZenseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            ZenseError::NoDeviceConnected => "no device connected",
+            ZenseError::InvalidDeviceIndex => "invalid device index",
+            ZenseError::DevicePointerIsNull => "device pointer is null",
+            ZenseError::InvalidFrameType => "invalid frame type",
+            ZenseError::FramePointerIsNull => "frame pointer is null",
+            ZenseError::NoPropertyValueGet => "property cannot be got",
+            ZenseError::NoPropertyValueSet => "property cannot be set",
+            ZenseError::PropertyPointerIsNull => "property pointer is null",
+            ZenseError::PropertySizeNotEnough => "property buffer is too small",
+            ZenseError::InvalidDepthRange => "invalid depth range",
+            ZenseError::ReadNextFrameError => "failed to read the next frame",
+            ZenseError::InputPointerIsNull => "input pointer is null",
+            ZenseError::CameraNotOpened => "camera not opened",
+            ZenseError::InvalidCameraType => "invalid camera type",
+            ZenseError::InvalidParams => "invalid parameters",
+            ZenseError::Others => "unspecified SDK error",
+            ZenseError::FfiError => "invalid value returned by the SDK",
+            ZenseError::RuntimeError => "worker thread failed",
+            ZenseError::AlreadyInitialized => "SDK already initialized",
+            ZenseError::InvalidSessionIndex => "invalid session index",
+            ZenseError::InvalidFrameFormat => "invalid frame type or pixel format",
+            ZenseError::InvalidFrameSize => "frame size does not match its resolution",
+            ZenseError::UnknownEnumValue => "unknown enum value returned by the SDK",
+            ZenseError::SdkNotAvailable => "SDK library not available",
+            ZenseError::SymbolNotFound => "function missing from the SDK library",
+            ZenseError::SessionStreaming => "session already streaming",
+            ZenseError::Unknown => "unknown SDK status",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for ZenseError {}
+
+/// An integer from the SDK which is not a value of the enum it was decoded into, such as a
+/// data mode added by a newer firmware.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct UnknownEnumValue {
+    pub enum_name: &'static str,
+    pub value: c_int,
+}
+
+impl fmt::Display for UnknownEnumValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a value of {}", self.value, self.enum_name)
+    }
+}
+
+impl std::error::Error for UnknownEnumValue {}
+
+impl From<UnknownEnumValue> for ZenseError {
+    fn from(_: UnknownEnumValue) -> Self {
+        ZenseError::UnknownEnumValue
+    }
+}
+
+// Implements `TryFrom<c_int>`, for decoding integers written by the SDK, which must not be
+// written into the enum itself as any value outside the variants is undefined behavior.
+macro_rules! impl_try_from_int {
+    ($ty:ident { $($variant:ident),* $(,)? }) => {
+        impl TryFrom<c_int> for $ty {
+            type Error = UnknownEnumValue;
+
+            fn try_from(value: c_int) -> Result<Self, Self::Error> {
+                $(
+                    if value == $ty::$variant as c_int {
+                        return Ok($ty::$variant);
+                    }
+                )*
+                Err(UnknownEnumValue {
+                    enum_name: stringify!($ty),
+                    value,
+                })
+            }
+        }
+    };
 }
 
 #[repr(C)]
@@ -67,6 +202,19 @@ pub enum PsDepthRange {
     XxFarRange,
 }
 
+impl_try_from_int!(PsDepthRange {
+    Unknown,
+    NearRange,
+    MidRange,
+    FarRange,
+    XNearRange,
+    XMidRange,
+    XFarRange,
+    XxNearRange,
+    XxMidRange,
+    XxFarRange,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum PsDataMode {
@@ -80,6 +228,17 @@ pub enum PsDataMode {
     WdrDepthAndIr,
 }
 
+impl_try_from_int!(PsDataMode {
+    DepthAndRgb30Fps,
+    IrAndRGB30Fps,
+    DepthAndIr30Fps,
+    NoCcd30Fps,
+    DepthAndIr15FpsRgb30Fps,
+    WdrDepth,
+    WdrIr,
+    WdrDepthAndIr,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum PsPropertyType {
@@ -91,12 +250,38 @@ pub enum PsPropertyType {
     DepthRangeList,
 }
 
+impl_try_from_int!(PsPropertyType {
+    SerialNumber,
+    FirmwareVersion,
+    HardwareVersion,
+    DataMode,
+    DataModeList,
+    DepthRangeList,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Clone, Debug)]
 pub enum PropertyValue {
     StringValue(CString),
     Uint8Value(u8),
     Int32ValueList(Vec<i32>),
+    // Originally added
+    DataModeValue(PsDataMode),
+}
+
+impl PropertyValue {
+    /// Whether this is the variant which `property_type` is read and written as.
+    pub fn is_value_of(&self, property_type: PsPropertyType) -> bool {
+        match property_type {
+            PsPropertyType::SerialNumber
+            | PsPropertyType::FirmwareVersion
+            | PsPropertyType::HardwareVersion => matches!(self, PropertyValue::StringValue(_)),
+            PsPropertyType::DataMode => matches!(self, PropertyValue::DataModeValue(_)),
+            PsPropertyType::DataModeList | PsPropertyType::DepthRangeList => {
+                matches!(self, PropertyValue::Int32ValueList(_))
+            }
+        }
+    }
 }
 
 #[repr(C)]
@@ -113,6 +298,18 @@ pub enum PsFrameType {
     WdrDepthFrame,
 }
 
+impl_try_from_int!(PsFrameType {
+    DepthFrame,
+    IrFrame,
+    GrayFrame,
+    RgbFrame,
+    MappedRgbFrame,
+    MappedDepthFrame,
+    MappedIrFrame,
+    ConfidenceFrame,
+    WdrDepthFrame,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum PsSensorType {
@@ -120,6 +317,11 @@ pub enum PsSensorType {
     RgbSensor = 2,
 }
 
+impl_try_from_int!(PsSensorType {
+    DepthSensor,
+    RgbSensor,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum PsPixelFormat {
@@ -130,6 +332,24 @@ pub enum PsPixelFormat {
     Bgr888,
 }
 
+impl PsPixelFormat {
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            PsPixelFormat::DepthMm16 | PsPixelFormat::Gray16 => 2,
+            PsPixelFormat::Gray8 => 1,
+            PsPixelFormat::Rgb888 | PsPixelFormat::Bgr888 => 3,
+        }
+    }
+}
+
+impl_try_from_int!(PsPixelFormat {
+    DepthMm16,
+    Gray16,
+    Gray8,
+    Rgb888,
+    Bgr888,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum PsFilterType {
@@ -137,6 +357,11 @@ pub enum PsFilterType {
     SmoothingFilter,
 }
 
+impl_try_from_int!(PsFilterType {
+    ComputeRealDepthFilter,
+    SmoothingFilter,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum PsWdrTotalRange {
@@ -144,6 +369,11 @@ pub enum PsWdrTotalRange {
     WdrTotalRangeThree = 3,
 }
 
+impl_try_from_int!(PsWdrTotalRange {
+    WdrTotalRangeTwo,
+    WdrTotalRangeThree,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum PsWdrStyle {
@@ -151,6 +381,11 @@ pub enum PsWdrStyle {
     Alternation,
 }
 
+impl_try_from_int!(PsWdrStyle {
+    Fusion,
+    Alternation,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum PsStreamType {
@@ -161,6 +396,14 @@ pub enum PsStreamType {
     Imu,
 }
 
+impl_try_from_int!(PsStreamType {
+    Depth,
+    Ir,
+    Rgb,
+    Audio,
+    Imu,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum PsResolution {
@@ -170,6 +413,13 @@ pub enum PsResolution {
     Res640x360,
 }
 
+impl_try_from_int!(PsResolution {
+    Res1920x1080,
+    Res1280x720,
+    Res640x480,
+    Res640x360,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum PsLinkType {
@@ -179,6 +429,13 @@ pub enum PsLinkType {
     Mipi,
 }
 
+impl_try_from_int!(PsLinkType {
+    Unknown,
+    Usb,
+    Socket,
+    Mipi,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum PsConnectStatus {
@@ -199,6 +456,13 @@ impl PsConnectStatus {
     }
 }
 
+impl_try_from_int!(PsConnectStatus {
+    ConnectUnknown,
+    Unconnected,
+    Connected,
+    Opened,
+});
+
 #[repr(C)]
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum PsDeviceType {
@@ -233,9 +497,28 @@ impl PsDeviceType {
     }
 }
 
+impl_try_from_int!(PsDeviceType {
+    Unknown,
+    None,
+    DcamUpdate,
+    Dcam305,
+    Dcam500,
+    Dcam700,
+    Dcam710,
+    Dcam800,
+    DcamMipi,
+    Dcam800Lite,
+    Max,
+});
+
 #[repr(u8)]
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub enum GmmGainEffectiveTime {
     Temporary = 0u8,
     Permanent = 1u8,
 }
+
+impl_try_from_int!(GmmGainEffectiveTime {
+    Temporary,
+    Permanent,
+});
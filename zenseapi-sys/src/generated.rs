@@ -0,0 +1,14 @@
+//! Bindings generated by bindgen from the headers of the SDK found by the build script, with
+//! the `bindgen` feature.
+//!
+//! The rest of the crate uses the hand-written types, which `layout` checks against these.
+
+#![allow(
+    non_upper_case_globals,
+    non_camel_case_types,
+    non_snake_case,
+    dead_code,
+    clippy::all
+)]
+
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
@@ -0,0 +1,269 @@
+// Compile-time checks of the hand-written types against the layout of the SDK headers, where
+// a mismatch would corrupt memory silently. The expected offsets are written down from
+// Vzense_define.h, and with the `bindgen` feature also compared against the generated types.
+
+use std::mem::{align_of, offset_of, size_of};
+
+use crate::enums::*;
+use crate::types::*;
+
+const POINTER: usize = size_of::<*mut u8>();
+
+macro_rules! assert_offsets {
+    ($ty:ty, size: $size:expr, align: $align:expr, $($field:ident: $offset:expr),* $(,)?) => {
+        const _: () = assert!(
+            size_of::<$ty>() == $size,
+            concat!("size of ", stringify!($ty), " differs from the header")
+        );
+        const _: () = assert!(
+            align_of::<$ty>() == $align,
+            concat!("alignment of ", stringify!($ty), " differs from the header")
+        );
+        $(const _: () = assert!(
+            offset_of!($ty, $field) == $offset,
+            concat!("offset of ", stringify!($ty), "::", stringify!($field), " differs from the header")
+        );)*
+    };
+}
+
+// #pragma pack(push, 1)
+assert_offsets!(PsRawFrame, size: 29 + POINTER, align: 1,
+    frame_index: 0,
+    frame_type: 4,
+    pixel_format: 8,
+    imu_frame_no: 12,
+    frame_data: 13,
+    data_len: 13 + POINTER,
+    exposure_time: 17 + POINTER,
+    depth_range: 21 + POINTER,
+    width: 25 + POINTER,
+    height: 27 + POINTER,
+);
+assert_offsets!(PsDeviceInfo, size: 318, align: 1,
+    session_count: 0,
+    device_type: 4,
+    uri: 8,
+    fw: 264,
+    status: 314,
+);
+assert_offsets!(PsWdrOutputMode, size: 19, align: 1,
+    total_range: 0,
+    range1: 4,
+    range1_count: 8,
+    range2: 9,
+    range2_count: 13,
+    range3: 14,
+    range3_count: 18,
+);
+assert_offsets!(PsRawWdrOutputMode, size: 19, align: 1,
+    total_range: 0,
+    range1: 4,
+    range1_count: 8,
+    range2: 9,
+    range2_count: 13,
+    range3: 14,
+    range3_count: 18,
+);
+assert_offsets!(PsGmmGain, size: 3, align: 1,
+    gain: 0,
+    option: 2,
+);
+assert_offsets!(PsVector3f, size: 12, align: 4,
+    x: 0,
+    y: 4,
+    z: 8,
+);
+assert_offsets!(PsDepthVector3, size: 12, align: 4,
+    depth_x: 0,
+    depth_y: 4,
+    depth_z: 8,
+);
+assert_offsets!(PsCameraParameters, size: 96, align: 8,
+    fx: 0,
+    fy: 8,
+    cx: 16,
+    cy: 24,
+    k1: 32,
+    k2: 40,
+    p1: 48,
+    p2: 56,
+    k3: 64,
+    k4: 72,
+    k5: 80,
+    k6: 88,
+);
+assert_offsets!(PsCameraExtrinsicParameters, size: 96, align: 8,
+    rotation: 0,
+    translation: 72,
+);
+
+// enums passed by value or inside the structs are C enums
+const _: () = assert!(size_of::<PsDepthRange>() == 4);
+const _: () = assert!(size_of::<PsDataMode>() == 4);
+const _: () = assert!(size_of::<PsPropertyType>() == 4);
+const _: () = assert!(size_of::<PsFrameType>() == 4);
+const _: () = assert!(size_of::<PsSensorType>() == 4);
+const _: () = assert!(size_of::<PsPixelFormat>() == 4);
+const _: () = assert!(size_of::<PsWdrTotalRange>() == 4);
+const _: () = assert!(size_of::<PsWdrStyle>() == 4);
+const _: () = assert!(size_of::<PsResolution>() == 4);
+const _: () = assert!(size_of::<GmmGainEffectiveTime>() == 1);
+
+#[cfg(feature = "bindgen")]
+mod generated {
+    use super::*;
+    use crate::generated as header;
+
+    macro_rules! assert_same_layout {
+        ($ty:ident => $header_ty:ident, $($field:ident => $header_field:ident),* $(,)?) => {
+            const _: () = assert!(
+                size_of::<$ty>() == size_of::<header::$header_ty>(),
+                concat!("size of ", stringify!($ty), " differs from the header")
+            );
+            const _: () = assert!(
+                align_of::<$ty>() == align_of::<header::$header_ty>(),
+                concat!("alignment of ", stringify!($ty), " differs from the header")
+            );
+            $(const _: () = assert!(
+                offset_of!($ty, $field) == offset_of!(header::$header_ty, $header_field),
+                concat!("offset of ", stringify!($ty), "::", stringify!($field), " differs from the header")
+            );)*
+        };
+    }
+
+    macro_rules! assert_same_values {
+        ($ty:ident, $($variant:ident => $header_const:ident),* $(,)?) => {
+            $(const _: () = assert!(
+                $ty::$variant as i64 == header::$header_const as i64,
+                concat!(stringify!($ty), "::", stringify!($variant), " differs from the header")
+            );)*
+        };
+    }
+
+    assert_same_layout!(PsRawFrame => PsFrame,
+        frame_index => frameIndex,
+        frame_type => frameType,
+        pixel_format => pixelFormat,
+        imu_frame_no => imuFrameNo,
+        frame_data => pFrameData,
+        data_len => dataLen,
+        exposure_time => exposureTime,
+        depth_range => depthRange,
+        width => width,
+        height => height,
+    );
+    assert_same_layout!(PsDeviceInfo => PsDeviceInfo,
+        session_count => sessionCount,
+        device_type => devicetype,
+        uri => uri,
+        fw => fw,
+        status => status,
+    );
+    assert_same_layout!(PsWdrOutputMode => PsWDROutputMode,
+        total_range => totalRange,
+        range1 => range1,
+        range1_count => range1Count,
+        range2 => range2,
+        range2_count => range2Count,
+        range3 => range3,
+        range3_count => range3Count,
+    );
+    assert_same_layout!(PsRawWdrOutputMode => PsWDROutputMode,
+        total_range => totalRange,
+        range1 => range1,
+        range1_count => range1Count,
+        range2 => range2,
+        range2_count => range2Count,
+        range3 => range3,
+        range3_count => range3Count,
+    );
+    assert_same_layout!(PsGmmGain => PsGMMGain,
+        gain => gain,
+        option => option,
+    );
+    assert_same_layout!(PsVector3f => PsVector3f,
+        x => x,
+        y => y,
+        z => z,
+    );
+    assert_same_layout!(PsDepthVector3 => PsDepthVector3,
+        depth_x => depthX,
+        depth_y => depthY,
+        depth_z => depthZ,
+    );
+    assert_same_layout!(PsCameraParameters => PsCameraParameters,
+        fx => fx, fy => fy, cx => cx, cy => cy,
+        k1 => k1, k2 => k2, p1 => p1, p2 => p2,
+        k3 => k3, k4 => k4, k5 => k5, k6 => k6,
+    );
+    assert_same_layout!(PsCameraExtrinsicParameters => PsCameraExtrinsicParameters,
+        rotation => rotation,
+        translation => translation,
+    );
+
+    assert_same_values!(PsDepthRange,
+        NearRange => PsDepthRange_PsNearRange,
+        MidRange => PsDepthRange_PsMidRange,
+        FarRange => PsDepthRange_PsFarRange,
+        XNearRange => PsDepthRange_PsXNearRange,
+        XMidRange => PsDepthRange_PsXMidRange,
+        XFarRange => PsDepthRange_PsXFarRange,
+        XxNearRange => PsDepthRange_PsXXNearRange,
+        XxMidRange => PsDepthRange_PsXXMidRange,
+        XxFarRange => PsDepthRange_PsXXFarRange,
+    );
+    assert_same_values!(PsDataMode,
+        DepthAndRgb30Fps => PsDataMode_PsDepthAndRGB_30,
+        IrAndRGB30Fps => PsDataMode_PsIRAndRGB_30,
+        DepthAndIr30Fps => PsDataMode_PsDepthAndIR_30,
+        NoCcd30Fps => PsDataMode_PsNoCCD_30,
+        DepthAndIr15FpsRgb30Fps => PsDataMode_PsDepthAndIR_15_RGB_30,
+        WdrDepth => PsDataMode_PsWDR_Depth,
+        WdrIr => PsDataMode_PsWDR_IR,
+        WdrDepthAndIr => PsDataMode_PsWDR_DepthAndIR,
+    );
+    assert_same_values!(PsPropertyType,
+        SerialNumber => PsPropertyType_PsPropertySN_Str,
+        FirmwareVersion => PsPropertyType_PsPropertyFWVer_Str,
+        HardwareVersion => PsPropertyType_PsPropertyHWVer_Str,
+        DataMode => PsPropertyType_PsPropertyDataMode_UInt8,
+        DataModeList => PsPropertyType_PsPropertyDataModeList,
+        DepthRangeList => PsPropertyType_PsPropertyDepthRangeList,
+    );
+    assert_same_values!(PsFrameType,
+        DepthFrame => PsFrameType_PsDepthFrame,
+        IrFrame => PsFrameType_PsIRFrame,
+        GrayFrame => PsFrameType_PsGrayFrame,
+        RgbFrame => PsFrameType_PsRGBFrame,
+        MappedRgbFrame => PsFrameType_PsMappedRGBFrame,
+        MappedDepthFrame => PsFrameType_PsMappedDepthFrame,
+        MappedIrFrame => PsFrameType_PsMappedIRFrame,
+        ConfidenceFrame => PsFrameType_PsConfidenceFrame,
+        WdrDepthFrame => PsFrameType_PsWDRDepthFrame,
+    );
+    assert_same_values!(PsSensorType,
+        DepthSensor => PsSensorType_PsDepthSensor,
+        RgbSensor => PsSensorType_PsRgbSensor,
+    );
+    assert_same_values!(PsPixelFormat,
+        DepthMm16 => PsPixelFormat_PsPixelFormatDepthMM16,
+        Gray16 => PsPixelFormat_PsPixelFormatGray16,
+        Gray8 => PsPixelFormat_PsPixelFormatGray8,
+        Rgb888 => PsPixelFormat_PsPixelFormatRGB888,
+        Bgr888 => PsPixelFormat_PsPixelFormatBGR888,
+    );
+    assert_same_values!(PsWdrTotalRange,
+        WdrTotalRangeTwo => PsWDRTotalRange_PsWDRTotalRange_Two,
+        WdrTotalRangeThree => PsWDRTotalRange_PsWDRTotalRange_Three,
+    );
+    assert_same_values!(PsWdrStyle,
+        Fusion => PsWDRStyle_PsWDR_FUSION,
+        Alternation => PsWDRStyle_PsWDR_ALTERNATION,
+    );
+    assert_same_values!(PsResolution,
+        Res1920x1080 => PsResolution_PsRGB_Resolution_1920_1080,
+        Res1280x720 => PsResolution_PsRGB_Resolution_1280_720,
+        Res640x480 => PsResolution_PsRGB_Resolution_640_480,
+        Res640x360 => PsResolution_PsRGB_Resolution_640_360,
+    );
+}
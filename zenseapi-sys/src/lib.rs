@@ -1,15 +1,31 @@
+// Device handles are opaque pointers owned by the SDK and only ever passed back to it.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use std::convert::TryFrom;
 use std::ffi::CString;
 use std::mem::MaybeUninit;
 use std::num::NonZeroU8;
+use std::os::raw::c_int;
 
 use crate::enums::*;
 use crate::raw_funcs::*;
 use crate::types::*;
 
 pub mod enums;
+#[cfg(feature = "bindgen")]
+pub mod generated;
+mod layout;
+#[cfg(feature = "runtime-loading")]
+pub mod loader;
 mod raw_funcs;
 pub mod types;
 
+/// Version of the SDK found at build time, if its headers or pkg-config tell it.
+pub const SDK_VERSION: Option<&str> = option_env!("VZENSE_SDK_VERSION");
+
+// returned when the SDK wrote an enum value unknown to this crate
+pub(crate) const UNKNOWN_ENUM_VALUE: PsReturnStatus = 251;
+
 pub fn initialize() -> Result<(), PsReturnStatus> {
     match unsafe { Ps2_Initialize() } {
         0 => Ok(()),
@@ -122,21 +138,20 @@ pub fn get_frame(
     session_index: u32,
     frame_type: PsFrameType,
 ) -> Result<PsFrame, PsReturnStatus> {
-    let mut frame_data: [u8; 0] = [0; 0];
-    let mut frame = PsFrame {
+    let mut frame = PsRawFrame {
         frame_index: 0,
-        frame_type,
-        pixel_format: PsPixelFormat::Bgr888,
+        frame_type: frame_type as c_int,
+        pixel_format: PsPixelFormat::Bgr888 as c_int,
         imu_frame_no: 0,
-        frame_data: frame_data.as_mut_ptr(),
+        frame_data: std::ptr::null_mut(),
         data_len: 0,
         exposure_time: 0.0,
-        depth_range: PsDepthRange::Unknown,
+        depth_range: PsDepthRange::Unknown as c_int,
         width: 0,
         height: 0,
     };
     match unsafe { Ps2_GetFrame(device_handle, session_index, frame_type, &mut frame) } {
-        0 => Ok(frame),
+        0 => PsFrame::try_from(frame).map_err(|_| UNKNOWN_ENUM_VALUE),
         n => Err(n),
     }
 }
@@ -156,9 +171,9 @@ pub fn get_data_mode(
     device_handle: PsDeviceHandle,
     session_index: u32,
 ) -> Result<PsDataMode, PsReturnStatus> {
-    let mut data_mode: PsDataMode = PsDataMode::DepthAndRgb30Fps;
+    let mut data_mode: c_int = PsDataMode::DepthAndRgb30Fps as c_int;
     match unsafe { Ps2_GetDataMode(device_handle, session_index, &mut data_mode) } {
-        0 => Ok(data_mode),
+        0 => PsDataMode::try_from(data_mode).map_err(|_| UNKNOWN_ENUM_VALUE),
         n => Err(n),
     }
 }
@@ -167,9 +182,9 @@ pub fn get_depth_range(
     device_handle: PsDeviceHandle,
     session_index: u32,
 ) -> Result<PsDepthRange, PsReturnStatus> {
-    let mut depth_range: PsDepthRange = PsDepthRange::Unknown;
+    let mut depth_range: c_int = PsDepthRange::Unknown as c_int;
     match unsafe { Ps2_GetDepthRange(device_handle, session_index, &mut depth_range) } {
-        0 => Ok(depth_range),
+        0 => PsDepthRange::try_from(depth_range).map_err(|_| UNKNOWN_ENUM_VALUE),
         n => Err(n),
     }
 }
@@ -261,54 +276,107 @@ pub fn get_property(
     session_index: u32,
     property_type: PsPropertyType,
 ) -> Result<PropertyValue, PsReturnStatus> {
-    let mut size: i32 = 128;
-    let mut data_buf = Vec::<i8>::with_capacity(size as usize);
-    let data = data_buf.as_mut_ptr();
-    match unsafe { Ps2_GetProperty(device_handle, session_index, property_type, data, &mut size) } {
-        0 => match property_type {
-            PsPropertyType::SerialNumber
-            | PsPropertyType::FirmwareVersion
-            | PsPropertyType::HardwareVersion => {
-                if data.is_null() {
-                    Err(255)
+    let mut capacity: i32 = 128;
+    loop {
+        let mut data_buf = vec![0_i8; capacity as usize];
+        // the SDK reads the capacity from `size`, and writes the length of the value to it
+        let mut size = capacity;
+        match unsafe {
+            Ps2_GetProperty(
+                device_handle,
+                session_index,
+                property_type,
+                data_buf.as_mut_ptr(),
+                &mut size,
+            )
+        } {
+            0 if size < 0 || size > capacity => return Err(255),
+            0 => return decode_property(property_type, &data_buf[..size as usize]),
+            // retry with the length which the SDK asked for, or twice the capacity otherwise
+            -9 if capacity < MAX_PROPERTY_SIZE => {
+                capacity = if size > capacity {
+                    size.min(MAX_PROPERTY_SIZE)
                 } else {
-                    let c_char_slice = unsafe { std::slice::from_raw_parts(data, size as usize) };
-                    let vec_nz_u8 = c_char_slice
-                        .iter()
-                        .map(|&x| x as u8)
-                        .take_while(|&x| x != 0)
-                        .map(|x| NonZeroU8::new(x).unwrap())
-                        .collect::<Vec<NonZeroU8>>();
-                    let cstring = CString::from(vec_nz_u8);
-                    Ok(PropertyValue::StringValue(cstring))
-                }
+                    (capacity * 2).min(MAX_PROPERTY_SIZE)
+                };
             }
-            PsPropertyType::DataMode
-            | PsPropertyType::DataModeList
-            | PsPropertyType::DepthRangeList => unimplemented!(),
+            n => return Err(n),
+        }
+    }
+}
+
+// largest buffer offered to Ps2_GetProperty()
+const MAX_PROPERTY_SIZE: i32 = 4096;
+
+fn decode_property(
+    property_type: PsPropertyType,
+    data: &[i8],
+) -> Result<PropertyValue, PsReturnStatus> {
+    match property_type {
+        PsPropertyType::SerialNumber
+        | PsPropertyType::FirmwareVersion
+        | PsPropertyType::HardwareVersion => {
+            let vec_nz_u8 = data
+                .iter()
+                .map(|&x| x as u8)
+                .take_while(|&x| x != 0)
+                .map(|x| NonZeroU8::new(x).unwrap())
+                .collect::<Vec<NonZeroU8>>();
+            Ok(PropertyValue::StringValue(CString::from(vec_nz_u8)))
+        }
+        PsPropertyType::DataMode => match data.first() {
+            Some(&value) => PsDataMode::try_from(c_int::from(value as u8))
+                .map(PropertyValue::DataModeValue)
+                .map_err(|_| UNKNOWN_ENUM_VALUE),
+            None => Err(255),
         },
-        n => Err(n),
+        PsPropertyType::DataModeList | PsPropertyType::DepthRangeList => {
+            let chunks = data.chunks_exact(4);
+            if !chunks.remainder().is_empty() {
+                return Err(255);
+            }
+            let values = chunks
+                .map(|bytes| {
+                    i32::from_ne_bytes([
+                        bytes[0] as u8,
+                        bytes[1] as u8,
+                        bytes[2] as u8,
+                        bytes[3] as u8,
+                    ])
+                })
+                .collect();
+            Ok(PropertyValue::Int32ValueList(values))
+        }
     }
 }
 
+/// Sets a property, failing with `InvalidParams` (-15) if `data` is not the variant of
+/// `property_type`. Strings are passed with their terminating NUL.
 pub fn set_property(
     device_handle: PsDeviceHandle,
     session_index: u32,
     property_type: PsPropertyType,
     data: PropertyValue,
 ) -> Result<(), PsReturnStatus> {
-    let d = match data {
-        PropertyValue::StringValue(s) => (s.as_ptr(), s.as_bytes().len()),
-        PropertyValue::Uint8Value(_) | PropertyValue::Int32ValueList(_) => unimplemented!(),
+    if !data.is_value_of(property_type) {
+        return Err(-15);
+    }
+    let data_buf: Vec<u8> = match data {
+        PropertyValue::StringValue(s) => s.into_bytes_with_nul(),
+        PropertyValue::Uint8Value(value) => vec![value],
+        PropertyValue::DataModeValue(data_mode) => vec![data_mode as u8],
+        PropertyValue::Int32ValueList(values) => values
+            .iter()
+            .flat_map(|value| value.to_ne_bytes().to_vec())
+            .collect(),
     };
-    let data_buf = d.0 as *const std::ffi::c_void;
-    let data_size: i32 = d.1 as i32;
+    let data_size = i32::try_from(data_buf.len()).map_err(|_| -15)?;
     match unsafe {
         Ps2_SetProperty(
             device_handle,
             session_index,
             property_type,
-            data_buf,
+            data_buf.as_ptr() as *const std::ffi::c_void,
             data_size,
         )
     } {
@@ -336,6 +404,24 @@ pub fn get_camera_parameters(
     }
 }
 
+pub fn get_camera_extrinsic_parameters(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+) -> Result<PsCameraExtrinsicParameters, PsReturnStatus> {
+    let mut camera_extrinsic_parameters: MaybeUninit<PsCameraExtrinsicParameters> =
+        MaybeUninit::uninit();
+    match unsafe {
+        Ps2_GetCameraExtrinsicParameters(
+            device_handle,
+            session_index,
+            camera_extrinsic_parameters.as_mut_ptr(),
+        )
+    } {
+        0 => Ok(unsafe { camera_extrinsic_parameters.assume_init() }),
+        n => Err(n),
+    }
+}
+
 pub fn set_wdr_output_mode(
     device_handle: PsDeviceHandle,
     session_index: u32,
@@ -347,6 +433,25 @@ pub fn set_wdr_output_mode(
     }
 }
 
+pub fn get_wdr_output_mode(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+) -> Result<PsWdrOutputMode, PsReturnStatus> {
+    let mut wdr_mode = PsRawWdrOutputMode {
+        total_range: 0,
+        range1: PsDepthRange::Unknown as c_int,
+        range1_count: 0,
+        range2: PsDepthRange::Unknown as c_int,
+        range2_count: 0,
+        range3: PsDepthRange::Unknown as c_int,
+        range3_count: 0,
+    };
+    match unsafe { Ps2_GetWDROutputMode(device_handle, session_index, &mut wdr_mode) } {
+        0 => PsWdrOutputMode::try_from(wdr_mode).map_err(|_| UNKNOWN_ENUM_VALUE),
+        n => Err(n),
+    }
+}
+
 pub fn set_wdr_style(
     device_handle: PsDeviceHandle,
     session_index: u32,
@@ -381,6 +486,20 @@ pub fn set_depth_distortion_correction_enabled(
     }
 }
 
+pub fn get_depth_distortion_correction_enabled(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+) -> Result<bool, PsReturnStatus> {
+    // read as a byte, as any nonzero value written by the SDK means true
+    let mut enabled: u8 = 0;
+    match unsafe {
+        Ps2_GetDepthDistortionCorrectionEnabled(device_handle, session_index, &mut enabled)
+    } {
+        0 => Ok(enabled != 0),
+        n => Err(n),
+    }
+}
+
 pub fn set_ir_distortion_correction_enabled(
     device_handle: PsDeviceHandle,
     session_index: u32,
@@ -392,6 +511,19 @@ pub fn set_ir_distortion_correction_enabled(
     }
 }
 
+pub fn get_ir_distortion_correction_enabled(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+) -> Result<bool, PsReturnStatus> {
+    let mut enabled: u8 = 0;
+    match unsafe {
+        Ps2_GetIrDistortionCorrectionEnabled(device_handle, session_index, &mut enabled)
+    } {
+        0 => Ok(enabled != 0),
+        n => Err(n),
+    }
+}
+
 pub fn set_rgb_distortion_correction_enabled(
     device_handle: PsDeviceHandle,
     session_index: u32,
@@ -403,6 +535,19 @@ pub fn set_rgb_distortion_correction_enabled(
     }
 }
 
+pub fn get_rgb_distortion_correction_enabled(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+) -> Result<bool, PsReturnStatus> {
+    let mut enabled: u8 = 0;
+    match unsafe {
+        Ps2_GetRGBDistortionCorrectionEnabled(device_handle, session_index, &mut enabled)
+    } {
+        0 => Ok(enabled != 0),
+        n => Err(n),
+    }
+}
+
 pub fn set_compute_real_depth_correction_enabled(
     device_handle: PsDeviceHandle,
     session_index: u32,
@@ -415,6 +560,19 @@ pub fn set_compute_real_depth_correction_enabled(
     }
 }
 
+pub fn get_compute_real_depth_correction_enabled(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+) -> Result<bool, PsReturnStatus> {
+    let mut enabled: u8 = 0;
+    match unsafe {
+        Ps2_GetComputeRealDepthCorrectionEnabled(device_handle, session_index, &mut enabled)
+    } {
+        0 => Ok(enabled != 0),
+        n => Err(n),
+    }
+}
+
 pub fn set_spatial_filter_enabled(
     device_handle: PsDeviceHandle,
     session_index: u32,
@@ -426,6 +584,17 @@ pub fn set_spatial_filter_enabled(
     }
 }
 
+pub fn get_spatial_filter_enabled(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+) -> Result<bool, PsReturnStatus> {
+    let mut enabled: u8 = 0;
+    match unsafe { Ps2_GetSpatialFilterEnabled(device_handle, session_index, &mut enabled) } {
+        0 => Ok(enabled != 0),
+        n => Err(n),
+    }
+}
+
 pub fn set_time_filter_enabled(
     device_handle: PsDeviceHandle,
     session_index: u32,
@@ -437,6 +606,17 @@ pub fn set_time_filter_enabled(
     }
 }
 
+pub fn get_time_filter_enabled(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+) -> Result<bool, PsReturnStatus> {
+    let mut enabled: u8 = 0;
+    match unsafe { Ps2_GetTimeFilterEnabled(device_handle, session_index, &mut enabled) } {
+        0 => Ok(enabled != 0),
+        n => Err(n),
+    }
+}
+
 pub fn set_mapper_enabled_rgb_to_depth(
     device_handle: PsDeviceHandle,
     session_index: u32,
@@ -448,6 +628,17 @@ pub fn set_mapper_enabled_rgb_to_depth(
     }
 }
 
+pub fn get_mapper_enabled_rgb_to_depth(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+) -> Result<bool, PsReturnStatus> {
+    let mut enabled: u8 = 0;
+    match unsafe { Ps2_GetMapperEnabledRGBToDepth(device_handle, session_index, &mut enabled) } {
+        0 => Ok(enabled != 0),
+        n => Err(n),
+    }
+}
+
 pub fn set_mapper_enabled_depth_to_rgb(
     device_handle: PsDeviceHandle,
     session_index: u32,
@@ -459,6 +650,17 @@ pub fn set_mapper_enabled_depth_to_rgb(
     }
 }
 
+pub fn get_mapper_enabled_depth_to_rgb(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+) -> Result<bool, PsReturnStatus> {
+    let mut enabled: u8 = 0;
+    match unsafe { Ps2_GetMapperEnabledDepthToRGB(device_handle, session_index, &mut enabled) } {
+        0 => Ok(enabled != 0),
+        n => Err(n),
+    }
+}
+
 pub fn set_rgb_resolution(
     device_handle: PsDeviceHandle,
     session_index: u32,
@@ -480,3 +682,73 @@ pub fn set_color_pixel_format(
         n => Err(n),
     }
 }
+
+pub fn convert_depth_to_world(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+    depth_vectors: &[PsDepthVector3],
+) -> Result<Vec<PsVector3f>, PsReturnStatus> {
+    let point_count = i32::try_from(depth_vectors.len()).map_err(|_| -15)?;
+    let mut world_vectors = vec![PsVector3f::default(); depth_vectors.len()];
+    match unsafe {
+        Ps2_ConvertDepthToWorld(
+            device_handle,
+            session_index,
+            depth_vectors.as_ptr() as *mut PsDepthVector3,
+            world_vectors.as_mut_ptr(),
+            point_count,
+        )
+    } {
+        0 => Ok(world_vectors),
+        n => Err(n),
+    }
+}
+
+pub fn convert_world_to_depth(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+    world_vectors: &[PsVector3f],
+) -> Result<Vec<PsDepthVector3>, PsReturnStatus> {
+    let point_count = i32::try_from(world_vectors.len()).map_err(|_| -15)?;
+    let mut depth_vectors = vec![PsDepthVector3::default(); world_vectors.len()];
+    match unsafe {
+        Ps2_ConvertWorldToDepth(
+            device_handle,
+            session_index,
+            world_vectors.as_ptr() as *mut PsVector3f,
+            depth_vectors.as_mut_ptr(),
+            point_count,
+        )
+    } {
+        0 => Ok(depth_vectors),
+        n => Err(n),
+    }
+}
+
+/// Converts every pixel of `depth_frame`, row by row, failing with `InvalidParams` (-15) if
+/// its data is shorter than `width * height` pixels of 16 bits.
+///
+/// # Safety
+///
+/// `depth_frame.frame_data` must point to `depth_frame.data_len` readable bytes.
+pub unsafe fn convert_depth_frame_to_world_vector(
+    device_handle: PsDeviceHandle,
+    session_index: u32,
+    depth_frame: &PsFrame,
+) -> Result<Vec<PsVector3f>, PsReturnStatus> {
+    let point_count = usize::from(depth_frame.width) * usize::from(depth_frame.height);
+    if depth_frame.frame_data.is_null() || (depth_frame.data_len as usize) < point_count * 2 {
+        return Err(-15);
+    }
+    let raw_frame = PsRawFrame::from(*depth_frame);
+    let mut world_vectors = vec![PsVector3f::default(); point_count];
+    match Ps2_ConvertDepthFrameToWorldVector(
+        device_handle,
+        session_index,
+        &raw_frame,
+        world_vectors.as_mut_ptr(),
+    ) {
+        0 => Ok(world_vectors),
+        n => Err(n),
+    }
+}
@@ -0,0 +1,170 @@
+//! Loading of libvzense_api at runtime, with the `runtime-loading` feature.
+//!
+//! The library is loaded by the first call into the SDK, from `VZENSE_SDK_PATH` or the
+//! search path of the dynamic loader, unless `load_from()` loaded it before. Until it is
+//! loaded, calls fail with `ZenseError::SdkNotAvailable`, and calls of functions missing
+//! from the library fail with `ZenseError::SymbolNotFound`.
+//!
+//! That first load is attempted once: if it fails, `load_error()` tells why and calls fail
+//! right away, until `load()` or `load_from()` succeeds.
+
+use std::ffi::{c_void, CStr, CString};
+use std::fmt;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::raw_funcs::Functions;
+use crate::types::PsReturnStatus;
+
+/// Environment variable naming the library, or the directory containing it.
+pub const SDK_PATH_VAR: &str = "VZENSE_SDK_PATH";
+pub const LIBRARY_NAME: &str = "libvzense_api.so";
+
+pub(crate) const SDK_NOT_AVAILABLE: PsReturnStatus = 253;
+pub(crate) const SYMBOL_NOT_FOUND: PsReturnStatus = 252;
+
+struct Library {
+    path: PathBuf,
+    functions: Functions,
+    missing_symbols: Vec<&'static str>,
+}
+
+// loaded once and never unloaded, so that the functions stay valid
+static LIBRARY: Mutex<Option<&'static Library>> = Mutex::new(None);
+// error of the load by the first call, which is not retried
+static LOAD_ERROR: Mutex<Option<LoadError>> = Mutex::new(None);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LoadError {
+    /// `dlopen()` failed, with the message of `dlerror()`.
+    Open { path: PathBuf, message: String },
+    /// Another library was loaded before.
+    AlreadyLoaded { path: PathBuf },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Open { path, message } => {
+                write!(f, "failed to load {}: {}", path.display(), message)
+            }
+            LoadError::AlreadyLoaded { path } => {
+                write!(f, "SDK is already loaded from {}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Loads the library from `VZENSE_SDK_PATH`, or by name from the search path if it is unset.
+pub fn load() -> Result<(), LoadError> {
+    match std::env::var_os(SDK_PATH_VAR) {
+        Some(path) if !path.is_empty() => load_from(path),
+        _ => load_from(LIBRARY_NAME),
+    }
+}
+
+/// Loads the library at `path`, or in `path` if it is a directory.
+///
+/// Succeeds without loading again if the same path was loaded before.
+pub fn load_from<P: AsRef<Path>>(path: P) -> Result<(), LoadError> {
+    let mut path = path.as_ref().to_path_buf();
+    if path.is_dir() {
+        path.push(LIBRARY_NAME);
+    }
+    let mut library = LIBRARY.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(loaded) = *library {
+        return if loaded.path == path {
+            Ok(())
+        } else {
+            Err(LoadError::AlreadyLoaded {
+                path: loaded.path.clone(),
+            })
+        };
+    }
+    let c_path = CString::new(path.as_os_str().as_bytes()).map_err(|_| LoadError::Open {
+        path: path.clone(),
+        message: "path contains a NUL byte".to_string(),
+    })?;
+    let handle = unsafe { libc::dlopen(c_path.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+    if handle.is_null() {
+        return Err(LoadError::Open {
+            path,
+            message: last_dl_error(),
+        });
+    }
+    let mut missing_symbols = Vec::new();
+    let functions = unsafe { Functions::resolve(handle, &mut missing_symbols) };
+    *library = Some(Box::leak(Box::new(Library {
+        path,
+        functions,
+        missing_symbols,
+    })));
+    Ok(())
+}
+
+pub fn is_loaded() -> bool {
+    loaded().is_some()
+}
+
+/// Path which the library was loaded from.
+pub fn loaded_path() -> Option<PathBuf> {
+    loaded().map(|library| library.path.clone())
+}
+
+/// Names of the `Ps2_*` functions which the loaded library lacks, which is empty until loaded.
+pub fn missing_symbols() -> Vec<&'static str> {
+    loaded().map_or_else(Vec::new, |library| library.missing_symbols.clone())
+}
+
+/// Why the load by the first call into the SDK failed, if it did.
+pub fn load_error() -> Option<LoadError> {
+    if is_loaded() {
+        return None;
+    }
+    LOAD_ERROR.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn loaded() -> Option<&'static Library> {
+    *LIBRARY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The functions of the library, loading it by `load()` on the first call.
+pub(crate) fn functions() -> Result<&'static Functions, PsReturnStatus> {
+    if let Some(library) = loaded() {
+        return Ok(&library.functions);
+    }
+    let mut load_error = LOAD_ERROR.lock().unwrap_or_else(|e| e.into_inner());
+    if load_error.is_some() {
+        return Err(SDK_NOT_AVAILABLE);
+    }
+    match load() {
+        Ok(()) => loaded()
+            .map(|library| &library.functions)
+            .ok_or(SDK_NOT_AVAILABLE),
+        Err(e) => {
+            *load_error = Some(e);
+            Err(SDK_NOT_AVAILABLE)
+        }
+    }
+}
+
+/// # Safety
+///
+/// `library` must be a handle returned by `dlopen()`, and `name` must end with a NUL byte.
+pub(crate) unsafe fn symbol(library: *mut c_void, name: &'static str) -> *mut c_void {
+    libc::dlsym(library, name.as_ptr() as *const libc::c_char)
+}
+
+fn last_dl_error() -> String {
+    let message = unsafe { libc::dlerror() };
+    if message.is_null() {
+        "unknown error".to_string()
+    } else {
+        unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
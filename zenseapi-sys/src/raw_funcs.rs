@@ -1,16 +1,80 @@
 use std::ffi::c_void;
-use std::os::raw::c_char;
+use std::os::raw::{c_char, c_int};
 
 use crate::enums::{PsPixelFormat, PsResolution, PsSensorType, PsWdrStyle};
 use crate::types::{
-    PsCameraParameters, PsDeviceHandle, PsFrameReady, PsReturnStatus, PsWdrOutputMode,
+    PsCameraExtrinsicParameters, PsCameraParameters, PsDepthVector3, PsDeviceHandle, PsFrameReady,
+    PsRawFrame, PsRawWdrOutputMode, PsReturnStatus, PsVector3f, PsWdrOutputMode,
 };
 use crate::{
-    enums::PsPropertyType, PsDataMode, PsDepthRange, PsDeviceInfo, PsFrame, PsFrameType, PsGmmGain,
+    enums::PsPropertyType, PsDataMode, PsDepthRange, PsDeviceInfo, PsFrameType, PsGmmGain,
 };
 
-#[link(name = "vzense_api")]
-extern "C" {
+// Every function of the SDK, which is linked at build time by build.rs, or resolved by `loader` at runtime
+// with the `runtime-loading` feature. Either way they are called as `Ps2_*()` from this crate.
+macro_rules! sdk_functions {
+    ($(pub fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> PsReturnStatus;)*) => {
+        // linked by build.rs
+        #[cfg(not(feature = "runtime-loading"))]
+        extern "C" {
+            $(pub fn $name($($arg: $ty),*) -> PsReturnStatus;)*
+        }
+
+        #[cfg(feature = "runtime-loading")]
+        #[allow(non_snake_case)]
+        pub(crate) struct Functions {
+            $($name: Option<unsafe extern "C" fn($($ty),*) -> PsReturnStatus>,)*
+        }
+
+        #[cfg(feature = "runtime-loading")]
+        impl Functions {
+            /// Looks every function up in `library`, pushing the names of missing ones to
+            /// `missing`.
+            ///
+            /// # Safety
+            ///
+            /// `library` must be a handle returned by `dlopen()` which is never closed.
+            pub(crate) unsafe fn resolve(
+                library: *mut c_void,
+                missing: &mut Vec<&'static str>,
+            ) -> Self {
+                Functions {
+                    $($name: {
+                        let symbol = crate::loader::symbol(
+                            library,
+                            concat!(stringify!($name), "\0"),
+                        );
+                        if symbol.is_null() {
+                            missing.push(stringify!($name));
+                            None
+                        } else {
+                            Some(std::mem::transmute::<
+                                *mut c_void,
+                                unsafe extern "C" fn($($ty),*) -> PsReturnStatus,
+                            >(symbol))
+                        }
+                    },)*
+                }
+            }
+        }
+
+        $(
+            #[cfg(feature = "runtime-loading")]
+            #[allow(non_snake_case)]
+            pub unsafe fn $name($($arg: $ty),*) -> PsReturnStatus {
+                match crate::loader::functions() {
+                    Ok(functions) => match functions.$name {
+                        Some(function) => function($($arg),*),
+                        None => crate::loader::SYMBOL_NOT_FOUND,
+                    },
+                    Err(status) => status,
+                }
+            }
+        )*
+    };
+}
+
+sdk_functions! {
     pub fn Ps2_Initialize() -> PsReturnStatus;
     pub fn Ps2_Shutdown() -> PsReturnStatus;
     pub fn Ps2_GetDeviceCount(device_count: *mut u32) -> PsReturnStatus;
@@ -35,7 +99,7 @@ extern "C" {
         device_handle: PsDeviceHandle,
         session_index: u32,
         frame_type: PsFrameType,
-        frame: *mut PsFrame,
+        frame: *mut PsRawFrame,
     ) -> PsReturnStatus;
 
     pub fn Ps2_SetDataMode(
@@ -46,13 +110,13 @@ extern "C" {
     pub fn Ps2_GetDataMode(
         device_handle: PsDeviceHandle,
         session_index: u32,
-        data_mode: *mut PsDataMode,
+        data_mode: *mut c_int,
     ) -> PsReturnStatus;
 
     pub fn Ps2_GetDepthRange(
         device_handle: PsDeviceHandle,
         session_index: u32,
-        depth_range: *mut PsDepthRange,
+        depth_range: *mut c_int,
     ) -> PsReturnStatus;
     pub fn Ps2_SetDepthRange(
         device_handle: PsDeviceHandle,
@@ -114,6 +178,11 @@ extern "C" {
         sensor_type: PsSensorType,
         camera_parameters: *mut PsCameraParameters,
     ) -> PsReturnStatus;
+    pub fn Ps2_GetCameraExtrinsicParameters(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        camera_extrinsic_parameters: *mut PsCameraExtrinsicParameters,
+    ) -> PsReturnStatus;
 
     //
 
@@ -122,6 +191,11 @@ extern "C" {
         session_index: u32,
         wdr_mode: *const PsWdrOutputMode,
     ) -> PsReturnStatus;
+    pub fn Ps2_GetWDROutputMode(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        wdr_mode: *mut PsRawWdrOutputMode,
+    ) -> PsReturnStatus;
 
     pub fn Ps2_SetWDRStyle(
         device_handle: PsDeviceHandle,
@@ -140,48 +214,88 @@ extern "C" {
         session_index: u32,
         enabled: bool,
     ) -> PsReturnStatus;
+    pub fn Ps2_GetDepthDistortionCorrectionEnabled(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: *mut u8,
+    ) -> PsReturnStatus;
 
     pub fn Ps2_SetIrDistortionCorrectionEnabled(
         device_handle: PsDeviceHandle,
         session_index: u32,
         enabled: bool,
     ) -> PsReturnStatus;
+    pub fn Ps2_GetIrDistortionCorrectionEnabled(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: *mut u8,
+    ) -> PsReturnStatus;
 
     pub fn Ps2_SetRGBDistortionCorrectionEnabled(
         device_handle: PsDeviceHandle,
         session_index: u32,
         enabled: bool,
     ) -> PsReturnStatus;
+    pub fn Ps2_GetRGBDistortionCorrectionEnabled(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: *mut u8,
+    ) -> PsReturnStatus;
 
     pub fn Ps2_SetComputeRealDepthCorrectionEnabled(
         device_handle: PsDeviceHandle,
         session_index: u32,
         enabled: bool,
     ) -> PsReturnStatus;
+    pub fn Ps2_GetComputeRealDepthCorrectionEnabled(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: *mut u8,
+    ) -> PsReturnStatus;
 
     pub fn Ps2_SetSpatialFilterEnabled(
         device_handle: PsDeviceHandle,
         session_index: u32,
         enabled: bool,
     ) -> PsReturnStatus;
+    pub fn Ps2_GetSpatialFilterEnabled(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: *mut u8,
+    ) -> PsReturnStatus;
 
     pub fn Ps2_SetTimeFilterEnabled(
         device_handle: PsDeviceHandle,
         session_index: u32,
         enabled: bool,
     ) -> PsReturnStatus;
+    pub fn Ps2_GetTimeFilterEnabled(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: *mut u8,
+    ) -> PsReturnStatus;
 
     pub fn Ps2_SetMapperEnabledRGBToDepth(
         device_handle: PsDeviceHandle,
         session_index: u32,
         enabled: bool,
     ) -> PsReturnStatus;
+    pub fn Ps2_GetMapperEnabledRGBToDepth(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: *mut u8,
+    ) -> PsReturnStatus;
 
     pub fn Ps2_SetMapperEnabledDepthToRGB(
         device_handle: PsDeviceHandle,
         session_index: u32,
         enabled: bool,
     ) -> PsReturnStatus;
+    pub fn Ps2_GetMapperEnabledDepthToRGB(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: *mut u8,
+    ) -> PsReturnStatus;
 
     pub fn Ps2_SetRGBResolution(
         device_handle: PsDeviceHandle,
@@ -194,4 +308,27 @@ extern "C" {
         session_index: u32,
         pixel_format: PsPixelFormat,
     ) -> PsReturnStatus;
+
+    // the input points and frame are not written by the SDK, despite the pointers being mutable
+    pub fn Ps2_ConvertDepthToWorld(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_vectors: *mut PsDepthVector3,
+        world_vectors: *mut PsVector3f,
+        point_count: i32,
+    ) -> PsReturnStatus;
+    pub fn Ps2_ConvertWorldToDepth(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        world_vectors: *mut PsVector3f,
+        depth_vectors: *mut PsDepthVector3,
+        point_count: i32,
+    ) -> PsReturnStatus;
+    // `depthFrame` is a C++ reference
+    pub fn Ps2_ConvertDepthFrameToWorldVector(
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_frame: *const PsRawFrame,
+        world_vectors: *mut PsVector3f,
+    ) -> PsReturnStatus;
 }
@@ -1,7 +1,9 @@
+use std::convert::TryFrom;
 use std::os::raw::{c_char, c_float, c_int};
 
 use crate::enums::{
     GmmGainEffectiveTime, PsDepthRange, PsFrameType, PsPixelFormat, PsWdrTotalRange,
+    UnknownEnumValue,
 };
 
 pub type PsReturnStatus = c_int;
@@ -24,7 +26,22 @@ pub struct Device {
     _private: [u8; 0],
 }
 
+/// `PsFrame` as written by the SDK, with its enums left as integers.
 #[repr(C, packed)]
+#[derive(Copy, Clone, Debug)]
+pub struct PsRawFrame {
+    pub frame_index: u32,
+    pub frame_type: c_int,
+    pub pixel_format: c_int,
+    pub imu_frame_no: u8,
+    pub frame_data: *mut u8,
+    pub data_len: u32,
+    pub exposure_time: c_float,
+    pub depth_range: c_int,
+    pub width: u16,
+    pub height: u16,
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct PsFrame {
     pub frame_index: u32,
@@ -39,6 +56,44 @@ pub struct PsFrame {
     pub height: u16,
 }
 
+impl From<PsFrame> for PsRawFrame {
+    fn from(frame: PsFrame) -> Self {
+        PsRawFrame {
+            frame_index: frame.frame_index,
+            frame_type: frame.frame_type as c_int,
+            pixel_format: frame.pixel_format as c_int,
+            imu_frame_no: frame.imu_frame_no,
+            frame_data: frame.frame_data,
+            data_len: frame.data_len,
+            exposure_time: frame.exposure_time,
+            depth_range: frame.depth_range as c_int,
+            width: frame.width,
+            height: frame.height,
+        }
+    }
+}
+
+impl TryFrom<PsRawFrame> for PsFrame {
+    type Error = UnknownEnumValue;
+
+    /// Fails on an unknown frame type or pixel format, without which the data cannot be read.
+    /// An unknown depth range becomes `PsDepthRange::Unknown`.
+    fn try_from(raw: PsRawFrame) -> Result<Self, Self::Error> {
+        Ok(PsFrame {
+            frame_index: raw.frame_index,
+            frame_type: PsFrameType::try_from(raw.frame_type)?,
+            pixel_format: PsPixelFormat::try_from(raw.pixel_format)?,
+            imu_frame_no: raw.imu_frame_no,
+            frame_data: raw.frame_data,
+            data_len: raw.data_len,
+            exposure_time: raw.exposure_time,
+            depth_range: PsDepthRange::try_from(raw.depth_range).unwrap_or(PsDepthRange::Unknown),
+            width: raw.width,
+            height: raw.height,
+        })
+    }
+}
+
 #[repr(C, packed)]
 #[derive(Copy, Clone)]
 pub struct PsGmmGain {
@@ -46,6 +101,24 @@ pub struct PsGmmGain {
     pub option: GmmGainEffectiveTime,
 }
 
+/// A point in the camera space, in millimeters.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PsVector3f {
+    pub x: c_float,
+    pub y: c_float,
+    pub z: c_float,
+}
+
+/// A pixel of a depth frame and its depth in millimeters.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct PsDepthVector3 {
+    pub depth_x: c_int,
+    pub depth_y: c_int,
+    pub depth_z: u16,
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
 pub struct PsCameraParameters {
@@ -63,6 +136,14 @@ pub struct PsCameraParameters {
     pub k6: f64,
 }
 
+/// Rotation, row-major, and translation in millimeters from the depth sensor to the RGB sensor.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct PsCameraExtrinsicParameters {
+    pub rotation: [f64; 9usize],
+    pub translation: [f64; 3usize],
+}
+
 #[repr(C, packed)]
 #[derive(Copy, Clone, Debug)]
 pub struct PsWdrOutputMode {
@@ -74,3 +155,35 @@ pub struct PsWdrOutputMode {
     pub range3: PsDepthRange,
     pub range3_count: u8,
 }
+
+/// `PsWdrOutputMode` as written by the SDK, with its enums left as integers.
+#[repr(C, packed)]
+#[derive(Copy, Clone, Debug)]
+pub struct PsRawWdrOutputMode {
+    pub total_range: c_int,
+    pub range1: c_int,
+    pub range1_count: u8,
+    pub range2: c_int,
+    pub range2_count: u8,
+    pub range3: c_int,
+    pub range3_count: u8,
+}
+
+impl TryFrom<PsRawWdrOutputMode> for PsWdrOutputMode {
+    type Error = UnknownEnumValue;
+
+    /// Fails on an unknown total range. Unknown ranges, such as those beyond the total range,
+    /// become `PsDepthRange::Unknown`.
+    fn try_from(raw: PsRawWdrOutputMode) -> Result<Self, Self::Error> {
+        let range = |value: c_int| PsDepthRange::try_from(value).unwrap_or(PsDepthRange::Unknown);
+        Ok(PsWdrOutputMode {
+            total_range: PsWdrTotalRange::try_from(raw.total_range)?,
+            range1: range(raw.range1),
+            range1_count: raw.range1_count,
+            range2: range(raw.range2),
+            range2_count: raw.range2_count,
+            range3: range(raw.range3),
+            range3_count: raw.range3_count,
+        })
+    }
+}
@@ -0,0 +1,91 @@
+use std::convert::TryFrom;
+
+use zenseapi_sys::enums::{
+    PsDataMode, PsDepthRange, PsFrameType, PsPixelFormat, PsWdrTotalRange, UnknownEnumValue,
+    ZenseError,
+};
+use zenseapi_sys::types::{PsFrame, PsRawFrame, PsRawWdrOutputMode, PsWdrOutputMode};
+
+#[test]
+fn known_values_decode() {
+    assert_eq!(PsDataMode::try_from(11), Ok(PsDataMode::WdrDepth));
+    assert_eq!(PsDepthRange::try_from(-1), Ok(PsDepthRange::Unknown));
+    assert_eq!(PsFrameType::try_from(8), Ok(PsFrameType::ConfidenceFrame));
+}
+
+#[test]
+fn unknown_values_are_errors() {
+    let e = PsDataMode::try_from(3).unwrap_err();
+    assert_eq!(
+        e,
+        UnknownEnumValue {
+            enum_name: "PsDataMode",
+            value: 3,
+        }
+    );
+    assert_eq!(e.to_string(), "3 is not a value of PsDataMode");
+    assert_eq!(ZenseError::from(e), ZenseError::UnknownEnumValue);
+    assert!(PsFrameType::try_from(7).is_err());
+    assert!(PsWdrTotalRange::try_from(4).is_err());
+}
+
+fn raw_frame() -> PsRawFrame {
+    PsRawFrame {
+        frame_index: 7,
+        frame_type: PsFrameType::DepthFrame as i32,
+        pixel_format: PsPixelFormat::DepthMm16 as i32,
+        imu_frame_no: 0,
+        frame_data: std::ptr::null_mut(),
+        data_len: 0,
+        exposure_time: 1.0,
+        depth_range: PsDepthRange::FarRange as i32,
+        width: 640,
+        height: 480,
+    }
+}
+
+#[test]
+fn raw_frames_decode() {
+    let frame = PsFrame::try_from(raw_frame()).unwrap();
+    assert_eq!(frame.frame_type, PsFrameType::DepthFrame);
+    assert_eq!(frame.depth_range, PsDepthRange::FarRange);
+    let raw = PsRawFrame::from(frame);
+    assert_eq!({ raw.pixel_format }, PsPixelFormat::DepthMm16 as i32);
+
+    // the data cannot be read without its format, but an unknown range is tolerated
+    let unknown_format = PsRawFrame {
+        pixel_format: 42,
+        ..raw_frame()
+    };
+    assert_eq!(PsFrame::try_from(unknown_format).unwrap_err().value, 42);
+    let unknown_range = PsRawFrame {
+        depth_range: 42,
+        ..raw_frame()
+    };
+    assert_eq!(
+        PsFrame::try_from(unknown_range).unwrap().depth_range,
+        PsDepthRange::Unknown
+    );
+}
+
+#[test]
+fn raw_wdr_output_modes_decode() {
+    let raw = PsRawWdrOutputMode {
+        total_range: 2,
+        range1: 0,
+        range1_count: 1,
+        range2: 42,
+        range2_count: 1,
+        range3: 0,
+        range3_count: 0,
+    };
+    let mode = PsWdrOutputMode::try_from(raw).unwrap();
+    assert_eq!({ mode.total_range }, PsWdrTotalRange::WdrTotalRangeTwo);
+    assert_eq!({ mode.range1 }, PsDepthRange::NearRange);
+    assert_eq!({ mode.range2 }, PsDepthRange::Unknown);
+    let unknown_total = PsRawWdrOutputMode {
+        total_range: 5,
+        ..raw
+    };
+    assert!(PsWdrOutputMode::try_from(unknown_total).is_err());
+}
@@ -0,0 +1,747 @@
+use raw::enums::{
+    GmmGainEffectiveTime, PropertyValue, PsDataMode, PsDepthRange, PsFrameType, PsPixelFormat,
+    PsPropertyType, PsResolution, PsSensorType, PsWdrStyle,
+};
+use raw::types::{
+    PsCameraExtrinsicParameters, PsCameraParameters, PsDepthVector3, PsDeviceHandle, PsDeviceInfo,
+    PsFrame, PsFrameReady, PsReturnStatus, PsVector3f, PsWdrOutputMode,
+};
+use zenseapi_sys as raw;
+
+/// Every operation of `zenseapi_sys`, so that the SDK can be swapped for another implementation.
+///
+/// Methods take and return the raw types of `zenseapi_sys`, and fail with a `PsReturnStatus`
+/// which `ZenseError::from_int()` understands. `SdkBackend` is the real SDK.
+///
+/// # Safety
+///
+/// Frames are read in place, so whenever `get_frame()` returns a `PsFrame` whose `data_len`
+/// matches its width, height and pixel format, `frame_data` must point to `data_len` readable
+/// bytes which stay valid and unchanged until the next `read_next_frame()` or `stop_stream()`
+/// of the session, or until the device is closed. Other lengths are rejected by the caller
+/// without reading.
+pub unsafe trait Backend: Send + Sync {
+    /// Whether the backend shares global state, like the SDK, so that at most one context may
+    /// run on it at a time. `Zense::with_backend()` refuses a second one with
+    /// `AlreadyInitialized`.
+    fn exclusive(&self) -> bool {
+        false
+    }
+
+    fn initialize(&self) -> Result<(), PsReturnStatus>;
+
+    fn shutdown(&self) -> Result<(), PsReturnStatus>;
+
+    fn get_device_count(&self) -> Result<u32, PsReturnStatus>;
+
+    fn get_device_list_info(&self, device_count: u32) -> Result<Vec<PsDeviceInfo>, PsReturnStatus>;
+
+    fn get_device_info(&self, device_index: u32) -> Result<PsDeviceInfo, PsReturnStatus>;
+
+    /// The returned handle is opaque to the caller, and is only passed back to this backend.
+    fn open_device(&self, uri: &str) -> Result<PsDeviceHandle, PsReturnStatus>;
+
+    fn close_device(&self, device_handle: PsDeviceHandle) -> Result<(), PsReturnStatus>;
+
+    fn start_stream(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn stop_stream(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn read_next_frame(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsFrameReady, PsReturnStatus>;
+
+    /// See the safety section of the trait for how long `PsFrame::frame_data` must stay valid.
+    fn get_frame(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        frame_type: PsFrameType,
+    ) -> Result<PsFrame, PsReturnStatus>;
+
+    fn set_data_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        data_mode: PsDataMode,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_data_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsDataMode, PsReturnStatus>;
+
+    fn get_depth_range(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsDepthRange, PsReturnStatus>;
+
+    fn set_depth_range(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_range: PsDepthRange,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_threshold(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<u16, PsReturnStatus>;
+
+    fn set_threshold(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        threshold: u16,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_pulse_count(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<u16, PsReturnStatus>;
+
+    fn set_pulse_count(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        pulse_count: u16,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_gmm_gain(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<u16, PsReturnStatus>;
+
+    fn set_gmm_gain(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        gmm_gain: u16,
+        option: GmmGainEffectiveTime,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_property(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        property_type: PsPropertyType,
+    ) -> Result<PropertyValue, PsReturnStatus>;
+
+    fn set_property(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        property_type: PsPropertyType,
+        data: PropertyValue,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_camera_parameters(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        sensor_type: PsSensorType,
+    ) -> Result<PsCameraParameters, PsReturnStatus>;
+
+    fn get_camera_extrinsic_parameters(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsCameraExtrinsicParameters, PsReturnStatus>;
+
+    fn set_wdr_output_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        wdr_mode: PsWdrOutputMode,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_wdr_output_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsWdrOutputMode, PsReturnStatus>;
+
+    fn set_wdr_style(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        wdr_style: PsWdrStyle,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn set_rgb_frame_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn set_depth_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_depth_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus>;
+
+    fn set_ir_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_ir_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus>;
+
+    fn set_rgb_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_rgb_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus>;
+
+    fn set_compute_real_depth_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_compute_real_depth_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus>;
+
+    fn set_spatial_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_spatial_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus>;
+
+    fn set_time_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_time_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus>;
+
+    fn set_mapper_enabled_rgb_to_depth(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_mapper_enabled_rgb_to_depth(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus>;
+
+    fn set_mapper_enabled_depth_to_rgb(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn get_mapper_enabled_depth_to_rgb(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus>;
+
+    fn set_rgb_resolution(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        resolution: PsResolution,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn set_color_pixel_format(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        pixel_format: PsPixelFormat,
+    ) -> Result<(), PsReturnStatus>;
+
+    fn convert_depth_to_world(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_vectors: &[PsDepthVector3],
+    ) -> Result<Vec<PsVector3f>, PsReturnStatus>;
+
+    fn convert_world_to_depth(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        world_vectors: &[PsVector3f],
+    ) -> Result<Vec<PsDepthVector3>, PsReturnStatus>;
+
+    /// Returns a point for every pixel of `depth_frame`, row by row.
+    ///
+    /// # Safety
+    ///
+    /// Unlike the frames the backend returns, `depth_frame` comes from the caller, which
+    /// guarantees that `depth_frame.frame_data` points to `depth_frame.data_len` readable bytes.
+    unsafe fn convert_depth_frame_to_world_vector(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_frame: &PsFrame,
+    ) -> Result<Vec<PsVector3f>, PsReturnStatus>;
+}
+
+/// `Backend` calling the Vzense SDK through `zenseapi_sys`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SdkBackend;
+
+// SAFETY: the SDK keeps the frame buffers until the next read or stop of the session.
+unsafe impl Backend for SdkBackend {
+    fn exclusive(&self) -> bool {
+        true
+    }
+
+    fn initialize(&self) -> Result<(), PsReturnStatus> {
+        raw::initialize()
+    }
+
+    fn shutdown(&self) -> Result<(), PsReturnStatus> {
+        raw::shutdown()
+    }
+
+    fn get_device_count(&self) -> Result<u32, PsReturnStatus> {
+        raw::get_device_count()
+    }
+
+    fn get_device_list_info(&self, device_count: u32) -> Result<Vec<PsDeviceInfo>, PsReturnStatus> {
+        raw::get_device_list_info(device_count)
+    }
+
+    fn get_device_info(&self, device_index: u32) -> Result<PsDeviceInfo, PsReturnStatus> {
+        raw::get_device_info(device_index)
+    }
+
+    fn open_device(&self, uri: &str) -> Result<PsDeviceHandle, PsReturnStatus> {
+        raw::open_device(uri)
+    }
+
+    fn close_device(&self, device_handle: PsDeviceHandle) -> Result<(), PsReturnStatus> {
+        raw::close_device(device_handle)
+    }
+
+    fn start_stream(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<(), PsReturnStatus> {
+        raw::start_stream(device_handle, session_index)
+    }
+
+    fn stop_stream(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<(), PsReturnStatus> {
+        raw::stop_stream(device_handle, session_index)
+    }
+
+    fn read_next_frame(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsFrameReady, PsReturnStatus> {
+        raw::read_next_frame(device_handle, session_index)
+    }
+
+    fn get_frame(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        frame_type: PsFrameType,
+    ) -> Result<PsFrame, PsReturnStatus> {
+        raw::get_frame(device_handle, session_index, frame_type)
+    }
+
+    fn set_data_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        data_mode: PsDataMode,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_data_mode(device_handle, session_index, data_mode)
+    }
+
+    fn get_data_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsDataMode, PsReturnStatus> {
+        raw::get_data_mode(device_handle, session_index)
+    }
+
+    fn get_depth_range(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsDepthRange, PsReturnStatus> {
+        raw::get_depth_range(device_handle, session_index)
+    }
+
+    fn set_depth_range(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_range: PsDepthRange,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_depth_range(device_handle, session_index, depth_range)
+    }
+
+    fn get_threshold(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<u16, PsReturnStatus> {
+        raw::get_threshold(device_handle, session_index)
+    }
+
+    fn set_threshold(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        threshold: u16,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_threshold(device_handle, session_index, threshold)
+    }
+
+    fn get_pulse_count(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<u16, PsReturnStatus> {
+        raw::get_pulse_count(device_handle, session_index)
+    }
+
+    fn set_pulse_count(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        pulse_count: u16,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_pulse_count(device_handle, session_index, pulse_count)
+    }
+
+    fn get_gmm_gain(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<u16, PsReturnStatus> {
+        raw::get_gmm_gain(device_handle, session_index)
+    }
+
+    fn set_gmm_gain(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        gmm_gain: u16,
+        option: GmmGainEffectiveTime,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_gmm_gain(device_handle, session_index, gmm_gain, option)
+    }
+
+    fn get_property(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        property_type: PsPropertyType,
+    ) -> Result<PropertyValue, PsReturnStatus> {
+        raw::get_property(device_handle, session_index, property_type)
+    }
+
+    fn set_property(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        property_type: PsPropertyType,
+        data: PropertyValue,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_property(device_handle, session_index, property_type, data)
+    }
+
+    fn get_camera_parameters(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        sensor_type: PsSensorType,
+    ) -> Result<PsCameraParameters, PsReturnStatus> {
+        raw::get_camera_parameters(device_handle, session_index, sensor_type)
+    }
+
+    fn get_camera_extrinsic_parameters(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsCameraExtrinsicParameters, PsReturnStatus> {
+        raw::get_camera_extrinsic_parameters(device_handle, session_index)
+    }
+
+    fn set_wdr_output_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        wdr_mode: PsWdrOutputMode,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_wdr_output_mode(device_handle, session_index, wdr_mode)
+    }
+
+    fn get_wdr_output_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsWdrOutputMode, PsReturnStatus> {
+        raw::get_wdr_output_mode(device_handle, session_index)
+    }
+
+    fn set_wdr_style(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        wdr_style: PsWdrStyle,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_wdr_style(device_handle, session_index, wdr_style)
+    }
+
+    fn set_rgb_frame_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_rgb_frame_enabled(device_handle, session_index, enabled)
+    }
+
+    fn set_depth_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_depth_distortion_correction_enabled(device_handle, session_index, enabled)
+    }
+
+    fn get_depth_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        raw::get_depth_distortion_correction_enabled(device_handle, session_index)
+    }
+
+    fn set_ir_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_ir_distortion_correction_enabled(device_handle, session_index, enabled)
+    }
+
+    fn get_ir_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        raw::get_ir_distortion_correction_enabled(device_handle, session_index)
+    }
+
+    fn set_rgb_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_rgb_distortion_correction_enabled(device_handle, session_index, enabled)
+    }
+
+    fn get_rgb_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        raw::get_rgb_distortion_correction_enabled(device_handle, session_index)
+    }
+
+    fn set_compute_real_depth_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_compute_real_depth_correction_enabled(device_handle, session_index, enabled)
+    }
+
+    fn get_compute_real_depth_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        raw::get_compute_real_depth_correction_enabled(device_handle, session_index)
+    }
+
+    fn set_spatial_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_spatial_filter_enabled(device_handle, session_index, enabled)
+    }
+
+    fn get_spatial_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        raw::get_spatial_filter_enabled(device_handle, session_index)
+    }
+
+    fn set_time_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_time_filter_enabled(device_handle, session_index, enabled)
+    }
+
+    fn get_time_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        raw::get_time_filter_enabled(device_handle, session_index)
+    }
+
+    fn set_mapper_enabled_rgb_to_depth(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_mapper_enabled_rgb_to_depth(device_handle, session_index, enabled)
+    }
+
+    fn get_mapper_enabled_rgb_to_depth(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        raw::get_mapper_enabled_rgb_to_depth(device_handle, session_index)
+    }
+
+    fn set_mapper_enabled_depth_to_rgb(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_mapper_enabled_depth_to_rgb(device_handle, session_index, enabled)
+    }
+
+    fn get_mapper_enabled_depth_to_rgb(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        raw::get_mapper_enabled_depth_to_rgb(device_handle, session_index)
+    }
+
+    fn set_rgb_resolution(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        resolution: PsResolution,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_rgb_resolution(device_handle, session_index, resolution)
+    }
+
+    fn set_color_pixel_format(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        pixel_format: PsPixelFormat,
+    ) -> Result<(), PsReturnStatus> {
+        raw::set_color_pixel_format(device_handle, session_index, pixel_format)
+    }
+
+    fn convert_depth_to_world(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_vectors: &[PsDepthVector3],
+    ) -> Result<Vec<PsVector3f>, PsReturnStatus> {
+        raw::convert_depth_to_world(device_handle, session_index, depth_vectors)
+    }
+
+    fn convert_world_to_depth(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        world_vectors: &[PsVector3f],
+    ) -> Result<Vec<PsDepthVector3>, PsReturnStatus> {
+        raw::convert_world_to_depth(device_handle, session_index, world_vectors)
+    }
+
+    unsafe fn convert_depth_frame_to_world_vector(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_frame: &PsFrame,
+    ) -> Result<Vec<PsVector3f>, PsReturnStatus> {
+        raw::convert_depth_frame_to_world_vector(device_handle, session_index, depth_frame)
+    }
+}
@@ -1,6 +1,6 @@
 use std::os::raw::c_int;
 
-pub use zenseapi_sys::enums::{GmmGainEffectiveTime, PropertyValue, ZenseError};
+pub use zenseapi_sys::enums::{GmmGainEffectiveTime, PropertyValue, UnknownEnumValue, ZenseError};
 use zenseapi_sys::enums::{
     PsDataMode, PsDepthRange, PsFilterType, PsFrameType, PsPixelFormat, PsPropertyType,
     PsResolution, PsSensorType, PsStreamType, PsWdrStyle, PsWdrTotalRange,
@@ -0,0 +1,189 @@
+use std::fmt;
+use std::os::raw::c_int;
+
+use crate::enums::ZenseError;
+
+/// Error of a call, with the SDK function, device and session it failed on where known.
+///
+/// `kind()` is the `ZenseError` which `ZenseError::from_int()` maps the status to, and
+/// `class()` tells whether retrying may help.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Error {
+    kind: ZenseError,
+    function: Option<&'static str>,
+    status: Option<c_int>,
+    device: Option<String>,
+    session_index: Option<u32>,
+    detail: Option<String>,
+}
+
+/// What can be done about an error, as given by `Error::class()`.
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Hash)]
+pub enum ErrorClass {
+    /// The call may succeed if repeated, such as reading a frame which was not ready.
+    Transient,
+    /// The device is gone or was closed, and has to be opened again.
+    DeviceLost,
+    /// The arguments or the state of the caller are wrong, so repeating fails the same way.
+    Misuse,
+    /// The device, firmware or SDK does not provide what was asked.
+    Unsupported,
+    /// Anything else, including unknown statuses of the SDK.
+    Other,
+}
+
+impl ErrorClass {
+    pub fn of(kind: ZenseError) -> Self {
+        match kind {
+            ZenseError::FramePointerIsNull
+            | ZenseError::ReadNextFrameError
+            | ZenseError::InvalidFrameSize => ErrorClass::Transient,
+            ZenseError::NoDeviceConnected
+            | ZenseError::DevicePointerIsNull
+            | ZenseError::CameraNotOpened => ErrorClass::DeviceLost,
+            ZenseError::InvalidDeviceIndex
+            | ZenseError::InvalidFrameType
+            | ZenseError::PropertyPointerIsNull
+            | ZenseError::PropertySizeNotEnough
+            | ZenseError::InvalidDepthRange
+            | ZenseError::InputPointerIsNull
+            | ZenseError::InvalidCameraType
+            | ZenseError::InvalidParams
+            | ZenseError::AlreadyInitialized
+            | ZenseError::InvalidSessionIndex
+            | ZenseError::InvalidFrameFormat
+            | ZenseError::SessionStreaming => ErrorClass::Misuse,
+            ZenseError::NoPropertyValueGet
+            | ZenseError::NoPropertyValueSet
+            | ZenseError::UnknownEnumValue
+            | ZenseError::SdkNotAvailable
+            | ZenseError::SymbolNotFound => ErrorClass::Unsupported,
+            ZenseError::Others
+            | ZenseError::FfiError
+            | ZenseError::RuntimeError
+            | ZenseError::Unknown => ErrorClass::Other,
+        }
+    }
+}
+
+impl Error {
+    /// Error of `function` of the SDK, which returned `status`.
+    pub(crate) fn from_status(function: &'static str, status: c_int) -> Self {
+        Error {
+            function: Some(function),
+            status: Some(status),
+            ..Error::from(ZenseError::from_int(status))
+        }
+    }
+
+    pub(crate) fn in_function(mut self, function: &'static str) -> Self {
+        self.function = Some(function);
+        self
+    }
+
+    pub(crate) fn on_device(mut self, uri: &str) -> Self {
+        self.device = Some(uri.to_string());
+        self
+    }
+
+    pub(crate) fn in_session(mut self, session_index: u32) -> Self {
+        self.session_index = Some(session_index);
+        self
+    }
+
+    pub fn kind(&self) -> ZenseError {
+        self.kind
+    }
+
+    pub fn class(&self) -> ErrorClass {
+        ErrorClass::of(self.kind)
+    }
+
+    /// Name of the SDK function which failed, such as `Ps2_ReadNextFrame`.
+    pub fn function(&self) -> Option<&'static str> {
+        self.function
+    }
+
+    /// Status returned by the SDK, which is absent for errors detected by this crate.
+    pub fn status(&self) -> Option<c_int> {
+        self.status
+    }
+
+    /// URI of the device.
+    pub fn device(&self) -> Option<&str> {
+        self.device.as_deref()
+    }
+
+    pub fn session_index(&self) -> Option<u32> {
+        self.session_index
+    }
+
+    /// Further explanation where known, such as why the SDK library could not be loaded.
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    pub fn is_transient(&self) -> bool {
+        self.class() == ErrorClass::Transient
+    }
+
+    pub fn is_device_lost(&self) -> bool {
+        self.class() == ErrorClass::DeviceLost
+    }
+}
+
+impl From<ZenseError> for Error {
+    fn from(kind: ZenseError) -> Self {
+        Error {
+            kind,
+            function: None,
+            status: None,
+            device: None,
+            session_index: None,
+            detail: detail_of(kind),
+        }
+    }
+}
+
+#[cfg(feature = "runtime-loading")]
+fn detail_of(kind: ZenseError) -> Option<String> {
+    match kind {
+        ZenseError::SdkNotAvailable => zenseapi_sys::loader::load_error().map(|e| e.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(not(feature = "runtime-loading"))]
+fn detail_of(_kind: ZenseError) -> Option<String> {
+    None
+}
+
+impl PartialEq<ZenseError> for Error {
+    fn eq(&self, kind: &ZenseError) -> bool {
+        self.kind == *kind
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(function) = self.function {
+            write!(f, "{}: ", function)?;
+        }
+        write!(f, "{}", self.kind)?;
+        if let Some(status) = self.status {
+            write!(f, " (status {})", status)?;
+        }
+        if let Some(device) = &self.device {
+            write!(f, " on device {}", device)?;
+        }
+        if let Some(session_index) = self.session_index {
+            write!(f, " in session {}", session_index)?;
+        }
+        if let Some(detail) = &self.detail {
+            write!(f, ": {}", detail)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,1060 @@
+//! A `Backend` which injects faults into the calls to another backend, for testing error
+//! handling and reconnection without making a camera fail.
+//!
+//! ```no_run
+//! use zenseapi::fault::{Fault, FaultBackend, FaultRule, Operation, Trigger};
+//! use zenseapi::sim::SimBackend;
+//! use zenseapi::enums::ZenseError;
+//! use zenseapi::Zense;
+//!
+//! let backend = FaultBackend::with_rules(
+//!     SimBackend::default(),
+//!     0,
+//!     vec![
+//!         FaultRule::new(
+//!             Operation::ReadNextFrame,
+//!             Trigger::Every(10),
+//!             Fault::Error(ZenseError::ReadNextFrameError),
+//!         ),
+//!         FaultRule::new(Operation::ReadNextFrame, Trigger::Nth(95), Fault::Unplug),
+//!     ],
+//! );
+//! let controller = backend.controller();
+//! let zense = Zense::with_backend(backend).unwrap();
+//! // ...
+//! controller.replug();
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use raw::enums::{
+    GmmGainEffectiveTime, PropertyValue, PsDataMode, PsDepthRange, PsFrameType, PsPixelFormat,
+    PsPropertyType, PsResolution, PsSensorType, PsWdrStyle,
+};
+use raw::types::{
+    PsCameraExtrinsicParameters, PsCameraParameters, PsDepthVector3, PsDeviceHandle, PsDeviceInfo,
+    PsFrame, PsFrameReady, PsReturnStatus, PsVector3f, PsWdrOutputMode,
+};
+use zenseapi_sys as raw;
+
+use crate::sim::Rng;
+use crate::{Backend, ZenseError};
+
+const NO_DEVICE_CONNECTED: PsReturnStatus = -1;
+const DEVICE_POINTER_IS_NULL: PsReturnStatus = -3;
+const CAMERA_NOT_OPENED: PsReturnStatus = -13;
+
+/// A method of `Backend`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Operation {
+    Initialize,
+    Shutdown,
+    GetDeviceCount,
+    GetDeviceListInfo,
+    GetDeviceInfo,
+    OpenDevice,
+    CloseDevice,
+    StartStream,
+    StopStream,
+    ReadNextFrame,
+    GetFrame,
+    SetDataMode,
+    GetDataMode,
+    GetDepthRange,
+    SetDepthRange,
+    GetThreshold,
+    SetThreshold,
+    GetPulseCount,
+    SetPulseCount,
+    GetGmmGain,
+    SetGmmGain,
+    GetProperty,
+    SetProperty,
+    GetCameraParameters,
+    GetCameraExtrinsicParameters,
+    SetWdrOutputMode,
+    GetWdrOutputMode,
+    SetWdrStyle,
+    SetRgbFrameEnabled,
+    SetDepthDistortionCorrectionEnabled,
+    GetDepthDistortionCorrectionEnabled,
+    SetIrDistortionCorrectionEnabled,
+    GetIrDistortionCorrectionEnabled,
+    SetRgbDistortionCorrectionEnabled,
+    GetRgbDistortionCorrectionEnabled,
+    SetComputeRealDepthCorrectionEnabled,
+    GetComputeRealDepthCorrectionEnabled,
+    SetSpatialFilterEnabled,
+    GetSpatialFilterEnabled,
+    SetTimeFilterEnabled,
+    GetTimeFilterEnabled,
+    SetMapperEnabledRgbToDepth,
+    GetMapperEnabledRgbToDepth,
+    SetMapperEnabledDepthToRgb,
+    GetMapperEnabledDepthToRgb,
+    SetRgbResolution,
+    SetColorPixelFormat,
+    ConvertDepthToWorld,
+    ConvertWorldToDepth,
+    ConvertDepthFrameToWorldVector,
+}
+
+/// Which calls of an operation a rule applies to. Calls are counted from 1 per operation,
+/// over the whole lifetime of the backend.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Trigger {
+    Always,
+    /// Only the nth call.
+    Nth(u64),
+    /// The nth call and every later one.
+    From(u64),
+    /// Every nth call, that is the nth, the 2nth and so on.
+    Every(u64),
+    /// Each call with this probability, drawn from the seeded generator of the backend.
+    Probability(f64),
+}
+
+impl Trigger {
+    fn fires(self, call_count: u64, rng: &mut Rng) -> bool {
+        match self {
+            Trigger::Always => true,
+            Trigger::Nth(n) => call_count == n,
+            Trigger::From(n) => call_count >= n,
+            Trigger::Every(n) => call_count.checked_rem(n) == Some(0),
+            Trigger::Probability(p) => rng.uniform() < p,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Fault {
+    /// Fails with the status code of the error, without calling the inner backend.
+    Error(ZenseError),
+    /// Sleeps before calling the inner backend.
+    Stall(Duration),
+    /// Unplugs every device, then fails with `NoDeviceConnected`.
+    Unplug,
+    /// Plugs the devices back, then calls the inner backend.
+    Replug,
+    /// On `ReadNextFrame`, reads and discards this many frames before the one returned,
+    /// which leaves a gap in the frame indices.
+    DropFrames(u32),
+    /// On `GetFrame`, reports this length instead of the length of the frame.
+    CorruptDataLen(u32),
+}
+
+/// Injects `fault` into the calls of `operation` which `trigger` selects.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FaultRule {
+    pub operation: Operation,
+    pub trigger: Trigger,
+    pub fault: Fault,
+}
+
+impl FaultRule {
+    /// # Panics
+    ///
+    /// Panics if `fault` is an error which the SDK never returns, such as
+    /// `InvalidSessionIndex`.
+    pub fn new(operation: Operation, trigger: Trigger, fault: Fault) -> Self {
+        if let Fault::Error(e) = fault {
+            assert!(
+                e.to_int().is_some(),
+                "{:?} is not a status of the SDK and cannot be injected",
+                e
+            );
+        }
+        FaultRule {
+            operation,
+            trigger,
+            fault,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    rules: Vec<FaultRule>,
+    call_counts: HashMap<Operation, u64>,
+    injected_count: u64,
+    rng: Rng,
+    plugged: bool,
+    // devices opened through the backend and not closed, by the handle returned for them
+    devices: HashMap<usize, OpenedDevice>,
+    // handle returned by the next open, so that a handle is never reused
+    next_handle: usize,
+}
+
+/// A device opened through the backend.
+#[derive(Debug)]
+struct OpenedDevice {
+    // handle of the device in the inner backend, as an integer to keep `State` `Send`
+    inner: usize,
+    // invalidated by an unplug
+    stale: bool,
+}
+
+impl State {
+    fn unplug(&mut self) {
+        self.plugged = false;
+        for device in self.devices.values_mut() {
+            device.stale = true;
+        }
+    }
+
+    fn inner_handle(
+        &self,
+        device_handle: PsDeviceHandle,
+    ) -> Result<PsDeviceHandle, PsReturnStatus> {
+        match self.devices.get(&(device_handle as usize)) {
+            Some(device) if device.stale => Err(CAMERA_NOT_OPENED),
+            Some(device) => Ok(device.inner as PsDeviceHandle),
+            None => Err(DEVICE_POINTER_IS_NULL),
+        }
+    }
+}
+
+/// Controls a `FaultBackend` after it was moved into a `Zense`.
+#[derive(Clone, Debug)]
+pub struct FaultController {
+    state: Arc<Mutex<State>>,
+}
+
+impl FaultController {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Adds `rule` after the existing rules. The first rule which fires on a call applies.
+    pub fn inject(&self, rule: FaultRule) {
+        self.lock().rules.push(rule);
+    }
+
+    /// Removes every rule. Call counts and the plug state are kept.
+    pub fn clear(&self) {
+        self.lock().rules.clear();
+    }
+
+    /// Unplugs every device, as if the cables were pulled.
+    ///
+    /// While unplugged, no device is listed and opening fails with `NoDeviceConnected`.
+    /// Devices which were open fail with `CameraNotOpened` until they are closed, even
+    /// after `replug()`. They stay open in the inner backend until then, so that frames
+    /// borrowed from them stay valid, and the inner backend may refuse to open them again
+    /// meanwhile.
+    pub fn unplug(&self) {
+        self.lock().unplug();
+    }
+
+    pub fn replug(&self) {
+        self.lock().plugged = true;
+    }
+
+    pub fn is_plugged(&self) -> bool {
+        self.lock().plugged
+    }
+
+    /// Number of calls of `operation` so far, including failed ones.
+    pub fn call_count(&self, operation: Operation) -> u64 {
+        self.lock()
+            .call_counts
+            .get(&operation)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Number of calls into which a fault was injected so far.
+    pub fn injected_count(&self) -> u64 {
+        self.lock().injected_count
+    }
+}
+
+/// A `Backend` which forwards to `inner` and injects faults according to its rules.
+///
+/// Rules can be given up front with `with_rules()`, or added later through `controller()`.
+/// The faults depend only on the rules, the seed and the sequence of calls.
+///
+/// Devices get handles of this backend, which are never reused, so that a device invalidated
+/// by an unplug stays invalid even if the inner backend hands out its handle again.
+#[derive(Debug)]
+pub struct FaultBackend<B> {
+    inner: B,
+    controller: FaultController,
+}
+
+impl<B: Backend> FaultBackend<B> {
+    pub fn new(inner: B, seed: u64) -> Self {
+        FaultBackend::with_rules(inner, seed, Vec::new())
+    }
+
+    pub fn with_rules(inner: B, seed: u64, rules: Vec<FaultRule>) -> Self {
+        let state = State {
+            rules,
+            call_counts: HashMap::new(),
+            injected_count: 0,
+            rng: Rng::new(seed),
+            plugged: true,
+            devices: HashMap::new(),
+            next_handle: 1,
+        };
+        FaultBackend {
+            inner,
+            controller: FaultController {
+                state: Arc::new(Mutex::new(state)),
+            },
+        }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    pub fn controller(&self) -> FaultController {
+        self.controller.clone()
+    }
+
+    /// Counts the call and applies the fault of the first rule which fires, returning it if
+    /// the call should go on to the inner backend, along with the handle of `device_handle`
+    /// in the inner backend, or null without a device.
+    fn begin(
+        &self,
+        operation: Operation,
+        device_handle: Option<PsDeviceHandle>,
+    ) -> Result<(Option<Fault>, PsDeviceHandle), PsReturnStatus> {
+        let mut state = self.controller.lock();
+        let state = &mut *state;
+        let call_count = state.call_counts.entry(operation).or_insert(0);
+        *call_count += 1;
+        let call_count = *call_count;
+        let inner_handle = match device_handle {
+            Some(device_handle) => state.inner_handle(device_handle)?,
+            None => std::ptr::null_mut(),
+        };
+        let rng = &mut state.rng;
+        let fault = state
+            .rules
+            .iter()
+            .find(|rule| rule.operation == operation && rule.trigger.fires(call_count, rng))
+            .map(|rule| rule.fault);
+        if fault.is_some() {
+            state.injected_count += 1;
+        }
+        match fault {
+            // FaultRule::new() accepts only errors with a status
+            Some(Fault::Error(e)) => return Err(e.to_int().unwrap_or(-255)),
+            Some(Fault::Unplug) => {
+                state.unplug();
+                return Err(NO_DEVICE_CONNECTED);
+            }
+            Some(Fault::Replug) => state.plugged = true,
+            _ => {}
+        }
+        if let Some(Fault::Stall(duration)) = fault {
+            std::thread::sleep(duration);
+        }
+        Ok((fault, inner_handle))
+    }
+
+    /// Calls `f` with the inner backend and the handle of `device_handle` in it.
+    fn call<T>(
+        &self,
+        operation: Operation,
+        device_handle: PsDeviceHandle,
+        f: impl FnOnce(&B, PsDeviceHandle) -> Result<T, PsReturnStatus>,
+    ) -> Result<T, PsReturnStatus> {
+        let (_, inner_handle) = self.begin(operation, Some(device_handle))?;
+        f(&self.inner, inner_handle)
+    }
+
+    fn is_plugged(&self) -> bool {
+        self.controller.is_plugged()
+    }
+}
+
+// SAFETY: frames of `inner` are passed through. A stale device can no longer read or stop its
+// streams, and is closed in `inner` only by closing its own handle, so its frames stay valid as
+// long as `inner` keeps them. A corrupted `data_len` never matches the frame size unless it is
+// the real length.
+unsafe impl<B: Backend> Backend for FaultBackend<B> {
+    fn exclusive(&self) -> bool {
+        self.inner.exclusive()
+    }
+
+    fn initialize(&self) -> Result<(), PsReturnStatus> {
+        self.begin(Operation::Initialize, None)?;
+        self.inner.initialize()
+    }
+
+    fn shutdown(&self) -> Result<(), PsReturnStatus> {
+        self.begin(Operation::Shutdown, None)?;
+        self.inner.shutdown()
+    }
+
+    fn get_device_count(&self) -> Result<u32, PsReturnStatus> {
+        self.begin(Operation::GetDeviceCount, None)?;
+        if !self.is_plugged() {
+            return Ok(0);
+        }
+        self.inner.get_device_count()
+    }
+
+    fn get_device_list_info(&self, device_count: u32) -> Result<Vec<PsDeviceInfo>, PsReturnStatus> {
+        self.begin(Operation::GetDeviceListInfo, None)?;
+        if !self.is_plugged() {
+            return Err(NO_DEVICE_CONNECTED);
+        }
+        self.inner.get_device_list_info(device_count)
+    }
+
+    fn get_device_info(&self, device_index: u32) -> Result<PsDeviceInfo, PsReturnStatus> {
+        self.begin(Operation::GetDeviceInfo, None)?;
+        if !self.is_plugged() {
+            return Err(NO_DEVICE_CONNECTED);
+        }
+        self.inner.get_device_info(device_index)
+    }
+
+    fn open_device(&self, uri: &str) -> Result<PsDeviceHandle, PsReturnStatus> {
+        self.begin(Operation::OpenDevice, None)?;
+        let mut state = self.controller.lock();
+        if !state.plugged {
+            return Err(NO_DEVICE_CONNECTED);
+        }
+        let inner_handle = self.inner.open_device(uri)?;
+        // the inner backend may hand out the handle of a closed device again, which must not
+        // make a stale handle of this backend usable
+        let device_handle = state.next_handle;
+        state.next_handle += 1;
+        state.devices.insert(
+            device_handle,
+            OpenedDevice {
+                inner: inner_handle as usize,
+                stale: false,
+            },
+        );
+        Ok(device_handle as PsDeviceHandle)
+    }
+
+    fn close_device(&self, device_handle: PsDeviceHandle) -> Result<(), PsReturnStatus> {
+        {
+            let mut state = self.controller.lock();
+            let key = device_handle as usize;
+            if state.devices.get(&key).is_some_and(|device| device.stale) {
+                let device = state.devices.remove(&key).unwrap();
+                *state.call_counts.entry(Operation::CloseDevice).or_insert(0) += 1;
+                let _ = self.inner.close_device(device.inner as PsDeviceHandle);
+                return Ok(());
+            }
+        }
+        let (_, inner_handle) = self.begin(Operation::CloseDevice, Some(device_handle))?;
+        self.controller
+            .lock()
+            .devices
+            .remove(&(device_handle as usize));
+        self.inner.close_device(inner_handle)
+    }
+
+    fn start_stream(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::StartStream,
+            device_handle,
+            |inner, device_handle| inner.start_stream(device_handle, session_index),
+        )
+    }
+
+    fn stop_stream(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::StopStream,
+            device_handle,
+            |inner, device_handle| inner.stop_stream(device_handle, session_index),
+        )
+    }
+
+    fn read_next_frame(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsFrameReady, PsReturnStatus> {
+        let (fault, device_handle) = self.begin(Operation::ReadNextFrame, Some(device_handle))?;
+        if let Some(Fault::DropFrames(count)) = fault {
+            for _ in 0..count {
+                self.inner.read_next_frame(device_handle, session_index)?;
+            }
+        }
+        self.inner.read_next_frame(device_handle, session_index)
+    }
+
+    fn get_frame(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        frame_type: PsFrameType,
+    ) -> Result<PsFrame, PsReturnStatus> {
+        let (fault, device_handle) = self.begin(Operation::GetFrame, Some(device_handle))?;
+        let mut frame = self
+            .inner
+            .get_frame(device_handle, session_index, frame_type)?;
+        if let Some(Fault::CorruptDataLen(data_len)) = fault {
+            frame.data_len = data_len;
+        }
+        Ok(frame)
+    }
+
+    fn set_data_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        data_mode: PsDataMode,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetDataMode,
+            device_handle,
+            |inner, device_handle| inner.set_data_mode(device_handle, session_index, data_mode),
+        )
+    }
+
+    fn get_data_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsDataMode, PsReturnStatus> {
+        self.call(
+            Operation::GetDataMode,
+            device_handle,
+            |inner, device_handle| inner.get_data_mode(device_handle, session_index),
+        )
+    }
+
+    fn get_depth_range(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsDepthRange, PsReturnStatus> {
+        self.call(
+            Operation::GetDepthRange,
+            device_handle,
+            |inner, device_handle| inner.get_depth_range(device_handle, session_index),
+        )
+    }
+
+    fn set_depth_range(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_range: PsDepthRange,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetDepthRange,
+            device_handle,
+            |inner, device_handle| inner.set_depth_range(device_handle, session_index, depth_range),
+        )
+    }
+
+    fn get_threshold(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<u16, PsReturnStatus> {
+        self.call(
+            Operation::GetThreshold,
+            device_handle,
+            |inner, device_handle| inner.get_threshold(device_handle, session_index),
+        )
+    }
+
+    fn set_threshold(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        threshold: u16,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetThreshold,
+            device_handle,
+            |inner, device_handle| inner.set_threshold(device_handle, session_index, threshold),
+        )
+    }
+
+    fn get_pulse_count(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<u16, PsReturnStatus> {
+        self.call(
+            Operation::GetPulseCount,
+            device_handle,
+            |inner, device_handle| inner.get_pulse_count(device_handle, session_index),
+        )
+    }
+
+    fn set_pulse_count(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        pulse_count: u16,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetPulseCount,
+            device_handle,
+            |inner, device_handle| inner.set_pulse_count(device_handle, session_index, pulse_count),
+        )
+    }
+
+    fn get_gmm_gain(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<u16, PsReturnStatus> {
+        self.call(
+            Operation::GetGmmGain,
+            device_handle,
+            |inner, device_handle| inner.get_gmm_gain(device_handle, session_index),
+        )
+    }
+
+    fn set_gmm_gain(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        gmm_gain: u16,
+        option: GmmGainEffectiveTime,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetGmmGain,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_gmm_gain(device_handle, session_index, gmm_gain, option)
+            },
+        )
+    }
+
+    fn get_property(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        property_type: PsPropertyType,
+    ) -> Result<PropertyValue, PsReturnStatus> {
+        self.call(
+            Operation::GetProperty,
+            device_handle,
+            |inner, device_handle| inner.get_property(device_handle, session_index, property_type),
+        )
+    }
+
+    fn set_property(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        property_type: PsPropertyType,
+        data: PropertyValue,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetProperty,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_property(device_handle, session_index, property_type, data)
+            },
+        )
+    }
+
+    fn get_camera_parameters(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        sensor_type: PsSensorType,
+    ) -> Result<PsCameraParameters, PsReturnStatus> {
+        self.call(
+            Operation::GetCameraParameters,
+            device_handle,
+            |inner, device_handle| {
+                inner.get_camera_parameters(device_handle, session_index, sensor_type)
+            },
+        )
+    }
+
+    fn get_camera_extrinsic_parameters(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsCameraExtrinsicParameters, PsReturnStatus> {
+        self.call(
+            Operation::GetCameraExtrinsicParameters,
+            device_handle,
+            |inner, device_handle| {
+                inner.get_camera_extrinsic_parameters(device_handle, session_index)
+            },
+        )
+    }
+
+    fn set_wdr_output_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        wdr_mode: PsWdrOutputMode,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetWdrOutputMode,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_wdr_output_mode(device_handle, session_index, wdr_mode)
+            },
+        )
+    }
+
+    fn get_wdr_output_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsWdrOutputMode, PsReturnStatus> {
+        self.call(
+            Operation::GetWdrOutputMode,
+            device_handle,
+            |inner, device_handle| inner.get_wdr_output_mode(device_handle, session_index),
+        )
+    }
+
+    fn set_wdr_style(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        wdr_style: PsWdrStyle,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetWdrStyle,
+            device_handle,
+            |inner, device_handle| inner.set_wdr_style(device_handle, session_index, wdr_style),
+        )
+    }
+
+    fn set_rgb_frame_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetRgbFrameEnabled,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_rgb_frame_enabled(device_handle, session_index, enabled)
+            },
+        )
+    }
+
+    fn set_depth_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetDepthDistortionCorrectionEnabled,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_depth_distortion_correction_enabled(device_handle, session_index, enabled)
+            },
+        )
+    }
+
+    fn get_depth_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.call(
+            Operation::GetDepthDistortionCorrectionEnabled,
+            device_handle,
+            |inner, device_handle| {
+                inner.get_depth_distortion_correction_enabled(device_handle, session_index)
+            },
+        )
+    }
+
+    fn set_ir_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetIrDistortionCorrectionEnabled,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_ir_distortion_correction_enabled(device_handle, session_index, enabled)
+            },
+        )
+    }
+
+    fn get_ir_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.call(
+            Operation::GetIrDistortionCorrectionEnabled,
+            device_handle,
+            |inner, device_handle| {
+                inner.get_ir_distortion_correction_enabled(device_handle, session_index)
+            },
+        )
+    }
+
+    fn set_rgb_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetRgbDistortionCorrectionEnabled,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_rgb_distortion_correction_enabled(device_handle, session_index, enabled)
+            },
+        )
+    }
+
+    fn get_rgb_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.call(
+            Operation::GetRgbDistortionCorrectionEnabled,
+            device_handle,
+            |inner, device_handle| {
+                inner.get_rgb_distortion_correction_enabled(device_handle, session_index)
+            },
+        )
+    }
+
+    fn set_compute_real_depth_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetComputeRealDepthCorrectionEnabled,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_compute_real_depth_correction_enabled(
+                    device_handle,
+                    session_index,
+                    enabled,
+                )
+            },
+        )
+    }
+
+    fn get_compute_real_depth_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.call(
+            Operation::GetComputeRealDepthCorrectionEnabled,
+            device_handle,
+            |inner, device_handle| {
+                inner.get_compute_real_depth_correction_enabled(device_handle, session_index)
+            },
+        )
+    }
+
+    fn set_spatial_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetSpatialFilterEnabled,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_spatial_filter_enabled(device_handle, session_index, enabled)
+            },
+        )
+    }
+
+    fn get_spatial_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.call(
+            Operation::GetSpatialFilterEnabled,
+            device_handle,
+            |inner, device_handle| inner.get_spatial_filter_enabled(device_handle, session_index),
+        )
+    }
+
+    fn set_time_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetTimeFilterEnabled,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_time_filter_enabled(device_handle, session_index, enabled)
+            },
+        )
+    }
+
+    fn get_time_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.call(
+            Operation::GetTimeFilterEnabled,
+            device_handle,
+            |inner, device_handle| inner.get_time_filter_enabled(device_handle, session_index),
+        )
+    }
+
+    fn set_mapper_enabled_rgb_to_depth(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetMapperEnabledRgbToDepth,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_mapper_enabled_rgb_to_depth(device_handle, session_index, enabled)
+            },
+        )
+    }
+
+    fn get_mapper_enabled_rgb_to_depth(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.call(
+            Operation::GetMapperEnabledRgbToDepth,
+            device_handle,
+            |inner, device_handle| {
+                inner.get_mapper_enabled_rgb_to_depth(device_handle, session_index)
+            },
+        )
+    }
+
+    fn set_mapper_enabled_depth_to_rgb(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetMapperEnabledDepthToRgb,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_mapper_enabled_depth_to_rgb(device_handle, session_index, enabled)
+            },
+        )
+    }
+
+    fn get_mapper_enabled_depth_to_rgb(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.call(
+            Operation::GetMapperEnabledDepthToRgb,
+            device_handle,
+            |inner, device_handle| {
+                inner.get_mapper_enabled_depth_to_rgb(device_handle, session_index)
+            },
+        )
+    }
+
+    fn set_rgb_resolution(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        resolution: PsResolution,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetRgbResolution,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_rgb_resolution(device_handle, session_index, resolution)
+            },
+        )
+    }
+
+    fn set_color_pixel_format(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        pixel_format: PsPixelFormat,
+    ) -> Result<(), PsReturnStatus> {
+        self.call(
+            Operation::SetColorPixelFormat,
+            device_handle,
+            |inner, device_handle| {
+                inner.set_color_pixel_format(device_handle, session_index, pixel_format)
+            },
+        )
+    }
+
+    fn convert_depth_to_world(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_vectors: &[PsDepthVector3],
+    ) -> Result<Vec<PsVector3f>, PsReturnStatus> {
+        self.call(
+            Operation::ConvertDepthToWorld,
+            device_handle,
+            |inner, device_handle| {
+                inner.convert_depth_to_world(device_handle, session_index, depth_vectors)
+            },
+        )
+    }
+
+    fn convert_world_to_depth(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        world_vectors: &[PsVector3f],
+    ) -> Result<Vec<PsDepthVector3>, PsReturnStatus> {
+        self.call(
+            Operation::ConvertWorldToDepth,
+            device_handle,
+            |inner, device_handle| {
+                inner.convert_world_to_depth(device_handle, session_index, world_vectors)
+            },
+        )
+    }
+
+    unsafe fn convert_depth_frame_to_world_vector(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_frame: &PsFrame,
+    ) -> Result<Vec<PsVector3f>, PsReturnStatus> {
+        self.call(
+            Operation::ConvertDepthFrameToWorldVector,
+            device_handle,
+            |inner, device_handle| unsafe {
+                inner.convert_depth_frame_to_world_vector(device_handle, session_index, depth_frame)
+            },
+        )
+    }
+}
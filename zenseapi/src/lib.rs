@@ -1,34 +1,222 @@
 use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use zenseapi_sys as raw;
+pub use zenseapi_sys as sys;
 
+pub use crate::backend::*;
 use crate::enums::*;
+pub use crate::error::*;
+pub use crate::pool::*;
+pub use crate::session::*;
+pub use crate::stream::*;
 pub use crate::structs::*;
+pub use crate::subscription::Subscription;
+pub use crate::typed::*;
+pub use crate::worker::*;
 
+mod backend;
 pub mod enums;
+mod error;
+pub mod fault;
+#[cfg(feature = "async")]
+pub mod nonblocking;
+mod pool;
+mod session;
+pub mod sim;
+mod stream;
 mod structs;
+mod subscription;
+mod typed;
+mod worker;
 
-pub type ZenseResult<T> = Result<T, ZenseError>;
+pub type ZenseResult<T> = Result<T, Error>;
 
-pub fn initialize() -> ZenseResult<()> {
-    match raw::initialize() {
-        Ok(()) => Ok(()),
-        Err(e) => Err(ZenseError::from_int(e)),
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+/// SDK context. The SDK is initialized by `Zense::new()` and shut down when this is dropped.
+///
+/// Only one context of the real SDK can exist at a time, whichever backend wraps it, and every
+/// device opened from a context borrows it, unless it was opened by `open_device_shared()` or
+/// `open_shared()`.
+pub struct Zense {
+    backend: Box<dyn Backend>,
+    // whether this context holds INITIALIZED
+    exclusive: bool,
+    shut_down: bool,
+}
+
+impl Zense {
+    pub fn new() -> ZenseResult<Self> {
+        Zense::with_backend(SdkBackend)
+    }
+
+    /// Creates a context running on `backend` instead of the SDK.
+    ///
+    /// Fails with `AlreadyInitialized` if the backend is exclusive, like the SDK wrapped in a
+    /// `FaultBackend`, and a context of an exclusive backend exists. Other backends may have
+    /// any number of contexts at once.
+    pub fn with_backend<B: Backend + 'static>(backend: B) -> ZenseResult<Self> {
+        let exclusive = backend.exclusive();
+        if exclusive
+            && INITIALIZED
+                .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+                .is_err()
+        {
+            return Err(ZenseError::AlreadyInitialized.into());
+        }
+        Zense::initialize(Box::new(backend), exclusive)
+    }
+
+    fn initialize(backend: Box<dyn Backend>, exclusive: bool) -> ZenseResult<Self> {
+        match backend.initialize() {
+            Ok(()) => Ok(Zense {
+                backend,
+                exclusive,
+                shut_down: false,
+            }),
+            Err(e) => {
+                if exclusive {
+                    INITIALIZED.store(false, Ordering::Release);
+                }
+                Err(Error::from_status("Ps2_Initialize", e))
+            }
+        }
+    }
+
+    pub(crate) fn backend(&self) -> &dyn Backend {
+        self.backend.as_ref()
+    }
+
+    /// Shuts down the SDK explicitly to observe the result, which `Drop` only logs.
+    pub fn shutdown(mut self) -> ZenseResult<()> {
+        self.shutdown_backend()
+    }
+
+    fn shutdown_backend(&mut self) -> ZenseResult<()> {
+        if self.shut_down {
+            return Ok(());
+        }
+        self.shut_down = true;
+        let result = self.backend.shutdown();
+        if self.exclusive {
+            INITIALIZED.store(false, Ordering::Release);
+        }
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => Err(Error::from_status("Ps2_Shutdown", e)),
+        }
+    }
+
+    pub fn get_device_count(&self) -> ZenseResult<u32> {
+        match self.backend.get_device_count() {
+            Ok(n) => Ok(n),
+            Err(e) => Err(Error::from_status("Ps2_GetDeviceCount", e)),
+        }
+    }
+
+    pub fn get_device_list_info(&self, device_count: u32) -> ZenseResult<Vec<DeviceInfo>> {
+        match self.backend.get_device_list_info(device_count) {
+            Ok(ps_device_info_vec) => Ok(ps_device_info_vec
+                .iter()
+                .map(|&ps_device_info| ps_device_info_to_device_info(ps_device_info))
+                .collect::<Vec<DeviceInfo>>()),
+            Err(e) => Err(Error::from_status("Ps2_GetDeviceListInfo", e)),
+        }
+    }
+
+    pub fn get_device_info(&self, device_index: u32) -> ZenseResult<DeviceInfo> {
+        match self.backend.get_device_info(device_index) {
+            Ok(ps_device_info) => Ok(ps_device_info_to_device_info(ps_device_info)),
+            Err(e) => Err(Error::from_status("Ps2_GetDeviceInfo", e)),
+        }
+    }
+
+    /// Opens the device at `uri`.
+    ///
+    /// The session count is taken from the device list. A device which is not listed under
+    /// the same URI is assumed to have a single session; use `open()` to avoid the lookup.
+    pub fn open_device(&self, uri: &str) -> ZenseResult<DeviceHandle<'_>> {
+        let session_count = self.session_count_of(uri)?;
+        open_with_session_count(ContextRef::Borrowed(self), uri, session_count)
+    }
+
+    /// Opens the device described by `device_info`.
+    pub fn open(&self, device_info: &DeviceInfo) -> ZenseResult<DeviceHandle<'_>> {
+        let uri = uri_of(device_info)?;
+        open_with_session_count(
+            ContextRef::Borrowed(self),
+            uri,
+            device_info.session_count as u32,
+        )
+    }
+
+    /// Like `open_device()`, but the device keeps a clone of `zense` instead of borrowing it,
+    /// so that it can be moved to another thread. The SDK is shut down once the last clone and
+    /// the last device opened this way are dropped.
+    pub fn open_device_shared(zense: &Arc<Zense>, uri: &str) -> ZenseResult<DeviceHandle<'static>> {
+        let session_count = zense.session_count_of(uri)?;
+        open_with_session_count(ContextRef::Shared(Arc::clone(zense)), uri, session_count)
+    }
+
+    /// Like `open()`, but the device keeps a clone of `zense`, see `open_device_shared()`.
+    pub fn open_shared(
+        zense: &Arc<Zense>,
+        device_info: &DeviceInfo,
+    ) -> ZenseResult<DeviceHandle<'static>> {
+        let uri = uri_of(device_info)?;
+        let session_count = device_info.session_count as u32;
+        open_with_session_count(ContextRef::Shared(Arc::clone(zense)), uri, session_count)
+    }
+
+    /// Session count of `uri` in the device list, or 1 if it is not listed.
+    fn session_count_of(&self, uri: &str) -> ZenseResult<u32> {
+        let device_count = self.get_device_count()?;
+        Ok(self
+            .get_device_list_info(device_count)?
+            .iter()
+            .find(|info| info.uri.to_str() == Ok(uri))
+            .map_or(1, |info| info.session_count as u32))
+    }
+}
+
+fn uri_of(device_info: &DeviceInfo) -> ZenseResult<&str> {
+    device_info
+        .uri
+        .to_str()
+        .map_err(|_| Error::from(ZenseError::InvalidParams).in_function("Ps2_OpenDevice"))
+}
+
+fn open_with_session_count<'a>(
+    context: ContextRef<'a>,
+    uri: &str,
+    session_count: u32,
+) -> ZenseResult<DeviceHandle<'a>> {
+    match context.zense().backend().open_device(uri) {
+        Ok(device) if device.is_null() => Err(Error::from(ZenseError::DevicePointerIsNull)
+            .in_function("Ps2_OpenDevice")
+            .on_device(uri)),
+        Ok(device) => Ok(DeviceHandle::new(context, device, uri, session_count)),
+        Err(e) => Err(Error::from_status("Ps2_OpenDevice", e).on_device(uri)),
     }
 }
 
-pub fn shutdown() -> ZenseResult<()> {
-    match raw::shutdown() {
-        Ok(()) => Ok(()),
-        Err(e) => Err(ZenseError::from_int(e)),
+impl std::fmt::Debug for Zense {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Zense")
+            .field("exclusive", &self.exclusive)
+            .field("shut_down", &self.shut_down)
+            .finish()
     }
 }
 
-pub fn get_device_count() -> ZenseResult<u32> {
-    match raw::get_device_count() {
-        Ok(n) => Ok(n),
-        Err(e) => Err(ZenseError::from_int(e)),
+impl Drop for Zense {
+    fn drop(&mut self) {
+        if let Err(e) = self.shutdown_backend() {
+            log::error!("Failed to shut down SDK: {}", e);
+        }
     }
 }
 
@@ -48,27 +236,3 @@ fn ps_device_info_to_device_info(ps_device_info: raw::types::PsDeviceInfo) -> De
         status: ConnectStatus::from_int(ps_device_info.status),
     }
 }
-
-pub fn get_device_list_info(device_count: u32) -> ZenseResult<Vec<DeviceInfo>> {
-    match raw::get_device_list_info(device_count) {
-        Ok(ps_device_info_vec) => Ok(ps_device_info_vec
-            .iter()
-            .map(|&ps_device_info| ps_device_info_to_device_info(ps_device_info))
-            .collect::<Vec<DeviceInfo>>()),
-        Err(e) => Err(ZenseError::from_int(e)),
-    }
-}
-
-pub fn get_device_info(device_index: u32) -> ZenseResult<DeviceInfo> {
-    match raw::get_device_info(device_index) {
-        Ok(ps_device_info) => Ok(ps_device_info_to_device_info(ps_device_info)),
-        Err(e) => Err(ZenseError::from_int(e)),
-    }
-}
-
-pub fn open_device(uri: &str) -> ZenseResult<DeviceHandle> {
-    match raw::open_device(uri) {
-        Ok(device) => Ok(DeviceHandle::new(device)),
-        Err(e) => Err(ZenseError::from_int(e)),
-    }
-}
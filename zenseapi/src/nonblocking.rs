@@ -0,0 +1,173 @@
+//! Asynchronous API, enabled by the `async` feature.
+//!
+//! Blocking SDK calls run on a pool of threads of their own, so this works with any async
+//! runtime.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::task::{Context, Poll};
+use std::thread;
+use std::time::Duration;
+
+use futures_channel::oneshot;
+use futures_core::Stream;
+
+use crate::{CaptureOptions, CaptureStats, CaptureWorker, DeviceHandle, FrameSet, Zense};
+use crate::{ZenseError, ZenseResult};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Threads running blocking calls, started on demand and kept a while for the next calls.
+struct BlockingPool {
+    jobs: Mutex<Jobs>,
+    available: Condvar,
+}
+
+struct Jobs {
+    queue: VecDeque<Job>,
+    // threads waiting for a job
+    idle: usize,
+}
+
+/// How long a thread of the pool waits for a job before exiting.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+static BLOCKING_POOL: BlockingPool = BlockingPool {
+    jobs: Mutex::new(Jobs {
+        queue: VecDeque::new(),
+        idle: 0,
+    }),
+    available: Condvar::new(),
+};
+
+impl BlockingPool {
+    fn lock(&self) -> MutexGuard<'_, Jobs> {
+        // jobs run outside the lock, so a poisoned lock leaves nothing half updated
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Runs `job` on an idle thread, or on a new one if every thread is busy.
+    fn run(&'static self, job: Job) -> ZenseResult<()> {
+        let mut jobs = self.lock();
+        jobs.queue.push_back(job);
+        if jobs.queue.len() <= jobs.idle {
+            self.available.notify_one();
+            return Ok(());
+        }
+        let spawned = thread::Builder::new()
+            .name(String::from("zense-blocking"))
+            .spawn(move || self.work());
+        if spawned.is_err() {
+            // dropped outside the lock, as dropping may block as well
+            let job = jobs.queue.pop_back();
+            drop(jobs);
+            drop(job);
+            return Err(ZenseError::RuntimeError.into());
+        }
+        Ok(())
+    }
+
+    fn work(&self) {
+        let mut jobs = self.lock();
+        loop {
+            if let Some(job) = jobs.queue.pop_front() {
+                drop(jobs);
+                job();
+                jobs = self.lock();
+                continue;
+            }
+            jobs.idle += 1;
+            let (guard, timeout) = self
+                .available
+                .wait_timeout(jobs, IDLE_TIMEOUT)
+                .unwrap_or_else(|e| e.into_inner());
+            jobs = guard;
+            jobs.idle -= 1;
+            if timeout.timed_out() && jobs.queue.is_empty() {
+                return;
+            }
+        }
+    }
+}
+
+/// Runs `f` on the blocking pool and waits for its result without blocking the executor.
+async fn unblock<T, F>(f: F) -> ZenseResult<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> ZenseResult<T> + Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    BLOCKING_POOL.run(Box::new(move || {
+        let _ = tx.send(f());
+    }))?;
+    rx.await.unwrap_or(Err(ZenseError::RuntimeError.into()))
+}
+
+/// Asynchronous `Zense::open_device_shared()`.
+pub async fn open_device(zense: &Arc<Zense>, uri: &str) -> ZenseResult<DeviceHandle<'static>> {
+    let zense = Arc::clone(zense);
+    let uri = String::from(uri);
+    unblock(move || Zense::open_device_shared(&zense, &uri)).await
+}
+
+/// Asynchronous `DeviceHandle::close_device()`.
+pub async fn close_device(device: DeviceHandle<'static>) -> ZenseResult<()> {
+    unblock(move || device.close_device()).await
+}
+
+/// A `futures_core::Stream` of captures, backed by a `CaptureWorker`.
+///
+/// Dropping it stops the stream and closes the device in the background.
+pub struct FrameStream {
+    worker: Option<CaptureWorker>,
+}
+
+impl FrameStream {
+    /// Asynchronous `CaptureWorker::spawn()`.
+    pub async fn start(
+        device: DeviceHandle<'static>,
+        options: CaptureOptions,
+    ) -> ZenseResult<Self> {
+        let worker = unblock(move || CaptureWorker::spawn(device, options)).await?;
+        Ok(FrameStream {
+            worker: Some(worker),
+        })
+    }
+
+    /// Stops the stream and gives the device back.
+    pub async fn stop(mut self) -> ZenseResult<DeviceHandle<'static>> {
+        match self.worker.take() {
+            Some(worker) => unblock(move || worker.stop()).await,
+            None => Err(ZenseError::RuntimeError.into()),
+        }
+    }
+
+    pub fn stats(&self) -> CaptureStats {
+        self.worker
+            .as_ref()
+            .map(CaptureWorker::stats)
+            .unwrap_or_default()
+    }
+}
+
+impl Stream for FrameStream {
+    type Item = ZenseResult<FrameSet>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match &self.worker {
+            Some(worker) => worker.poll_recv(cx),
+            None => Poll::Ready(None),
+        }
+    }
+}
+
+impl Drop for FrameStream {
+    fn drop(&mut self) {
+        // joining the capture thread may wait for a frame, so it is left to another thread
+        if let Some(worker) = self.worker.take() {
+            // without a thread, the job and so the worker are dropped right away
+            let _ = BLOCKING_POOL.run(Box::new(move || drop(worker)));
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+use crate::FrameType;
+
+/// Frame type, width and height of the frames a buffer is recycled for.
+type BufferKey = (i32, u16, u16);
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct FramePoolStats {
+    /// Buffers allocated because none was idle.
+    pub allocated: u64,
+    /// Buffers handed out again after being returned.
+    pub reused: u64,
+    /// Buffers currently held by frames.
+    pub in_use: usize,
+    /// Buffers waiting in the pool.
+    pub idle: usize,
+    /// Largest `in_use` seen so far.
+    pub high_water: usize,
+}
+
+#[derive(Debug)]
+struct PoolState {
+    idle: HashMap<BufferKey, Vec<Vec<u8>>>,
+    stats: FramePoolStats,
+}
+
+#[derive(Debug)]
+struct PoolInner {
+    state: Mutex<PoolState>,
+    max_idle_per_key: usize,
+}
+
+impl PoolInner {
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn release(&self, key: BufferKey, buffer: Vec<u8>) {
+        let mut state = self.lock();
+        state.stats.in_use -= 1;
+        let idle = state.idle.entry(key).or_default();
+        if idle.len() < self.max_idle_per_key {
+            idle.push(buffer);
+            state.stats.idle += 1;
+        }
+    }
+
+    /// Forgets a buffer which leaves the pool for good.
+    fn detach(&self) {
+        self.lock().stats.in_use -= 1;
+    }
+}
+
+/// Recycles frame buffers, so that capturing does not allocate once the pool is warm.
+///
+/// Buffers are kept per frame type and resolution, and frames return theirs on drop.
+/// Attach a pool with `Stream::set_frame_pool()` or `CaptureOptions::frame_pool`.
+/// Clones share the same buffers.
+#[derive(Clone, Debug)]
+pub struct FramePool {
+    inner: Arc<PoolInner>,
+}
+
+impl FramePool {
+    /// Creates a pool keeping up to `max_idle_per_key` idle buffers per frame type and resolution.
+    pub fn new(max_idle_per_key: usize) -> Self {
+        FramePool {
+            inner: Arc::new(PoolInner {
+                state: Mutex::new(PoolState {
+                    idle: HashMap::new(),
+                    stats: FramePoolStats::default(),
+                }),
+                max_idle_per_key,
+            }),
+        }
+    }
+
+    /// Returns a buffer of `len` bytes for a frame of `frame_type` in `width` x `height`.
+    ///
+    /// Its content is unspecified.
+    pub fn acquire(
+        &self,
+        frame_type: FrameType,
+        width: u16,
+        height: u16,
+        len: usize,
+    ) -> FrameBuffer {
+        let key = (frame_type as i32, width, height);
+        let mut state = self.inner.lock();
+        let recycled = state.idle.get_mut(&key).and_then(Vec::pop);
+        let data = match recycled {
+            Some(mut data) => {
+                state.stats.idle -= 1;
+                state.stats.reused += 1;
+                data.resize(len, 0);
+                data
+            }
+            None => {
+                state.stats.allocated += 1;
+                vec![0; len]
+            }
+        };
+        state.stats.in_use += 1;
+        state.stats.high_water = state.stats.high_water.max(state.stats.in_use);
+        FrameBuffer {
+            data,
+            pool: Some((Arc::downgrade(&self.inner), key)),
+        }
+    }
+
+    pub fn stats(&self) -> FramePoolStats {
+        self.inner.lock().stats
+    }
+
+    /// Frees every idle buffer.
+    pub fn clear(&self) {
+        let mut state = self.inner.lock();
+        state.idle.clear();
+        state.stats.idle = 0;
+    }
+}
+
+/// Pixel data of a `Frame`. If it came from a `FramePool`, it goes back there on drop.
+pub struct FrameBuffer {
+    data: Vec<u8>,
+    pool: Option<(Weak<PoolInner>, BufferKey)>,
+}
+
+impl FrameBuffer {
+    /// Takes the data out, detaching it from its pool.
+    pub fn into_vec(mut self) -> Vec<u8> {
+        if let Some((pool, _)) = self.pool.take() {
+            if let Some(pool) = pool.upgrade() {
+                pool.detach();
+            }
+        }
+        std::mem::take(&mut self.data)
+    }
+
+    pub fn is_pooled(&self) -> bool {
+        self.pool.is_some()
+    }
+}
+
+impl From<Vec<u8>> for FrameBuffer {
+    fn from(data: Vec<u8>) -> Self {
+        FrameBuffer { data, pool: None }
+    }
+}
+
+impl Deref for FrameBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl DerefMut for FrameBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+impl AsRef<[u8]> for FrameBuffer {
+    fn as_ref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// Clones are plain copies which do not belong to the pool.
+impl Clone for FrameBuffer {
+    fn clone(&self) -> Self {
+        FrameBuffer::from(self.data.clone())
+    }
+}
+
+impl std::fmt::Debug for FrameBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameBuffer")
+            .field("len", &self.data.len())
+            .field("pooled", &self.is_pooled())
+            .finish()
+    }
+}
+
+impl Drop for FrameBuffer {
+    fn drop(&mut self) {
+        if let Some((pool, key)) = self.pool.take() {
+            if let Some(pool) = pool.upgrade() {
+                pool.release(key, std::mem::take(&mut self.data));
+            }
+        }
+    }
+}
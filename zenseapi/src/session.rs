@@ -0,0 +1,662 @@
+use std::cell::RefMut;
+use std::convert::TryFrom;
+use std::os::raw::c_int;
+
+use zenseapi_sys::types::{PsFrame, PsVector3f};
+
+use crate::enums::{
+    GmmGainEffectiveTime, PropertyType, PropertyValue, Resolution, SensorType, UnknownEnumValue,
+    WdrStyle,
+};
+use crate::{
+    CameraExtrinsics, CameraParameters, DataMode, DepthFrame, DepthRange, DepthVector3,
+    DeviceHandle, Error, LastSet, PixelFormat, Stream, WdrOutputMode, ZenseError, ZenseResult,
+};
+
+/// A session of an opened device, obtained by `DeviceHandle::session()`.
+#[derive(Debug)]
+pub struct Session<'a> {
+    pub(crate) device: &'a DeviceHandle<'a>,
+    pub(crate) session_index: u32,
+}
+
+impl<'a> Session<'a> {
+    pub(crate) fn new(device: &'a DeviceHandle<'a>, session_index: u32) -> Self {
+        Session {
+            device,
+            session_index,
+        }
+    }
+
+    pub fn session_index(&self) -> u32 {
+        self.session_index
+    }
+
+    /// Error of `function` of the SDK, which returned `status` for this session.
+    pub(crate) fn error(&self, function: &'static str, status: c_int) -> Error {
+        self.device
+            .error(function, status)
+            .in_session(self.session_index)
+    }
+
+    /// Error found by this crate around a call of `function` of the SDK for this session.
+    pub(crate) fn error_in(&self, function: &'static str, kind: ZenseError) -> Error {
+        Error::from(kind)
+            .in_function(function)
+            .on_device(self.device.uri())
+            .in_session(self.session_index)
+    }
+
+    /// Starts streaming. The stream is stopped when the returned guard is dropped.
+    ///
+    /// The session stays readable through the guard, but calls which the SDK only accepts
+    /// while stopped, such as `set_data_mode()`, are unavailable until it is dropped.
+    ///
+    /// Fails with `SessionStreaming` while another `Session` of the same index streams.
+    pub fn start_stream(&mut self) -> ZenseResult<Stream<'_>> {
+        self.check_not_streaming("Ps2_StartStream")?;
+        match self
+            .device
+            .backend()
+            .start_stream(self.device.device_handle, self.session_index)
+        {
+            Ok(()) => {
+                self.device.streaming[self.session_index as usize].set(true);
+                Ok(Stream::new(self))
+            }
+            Err(n) => Err(self.error("Ps2_StartStream", n)),
+        }
+    }
+
+    /// Fails with `SessionStreaming` while a `Stream` of this session index exists, which
+    /// frames borrowed from its SDK buffer rely on.
+    fn check_not_streaming(&self, function: &'static str) -> ZenseResult<()> {
+        if self.device.streaming[self.session_index as usize].get() {
+            Err(self.error_in(function, ZenseError::SessionStreaming))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Fails with `SessionStreaming` while another `Session` of the same index streams.
+    pub fn set_data_mode(&mut self, data_mode: DataMode) -> ZenseResult<()> {
+        self.check_not_streaming("Ps2_SetDataMode")?;
+        match self.device.backend().set_data_mode(
+            self.device.device_handle,
+            self.session_index,
+            data_mode,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetDataMode", n)),
+        }
+    }
+
+    pub fn get_data_mode(&self) -> ZenseResult<DataMode> {
+        match self
+            .device
+            .backend()
+            .get_data_mode(self.device.device_handle, self.session_index)
+        {
+            Ok(data_mode) => Ok(data_mode),
+            Err(n) => Err(self.error("Ps2_GetDataMode", n)),
+        }
+    }
+
+    pub fn get_depth_range(&self) -> ZenseResult<DepthRange> {
+        match self
+            .device
+            .backend()
+            .get_depth_range(self.device.device_handle, self.session_index)
+        {
+            Ok(depth_range) => Ok(depth_range),
+            Err(n) => Err(self.error("Ps2_GetDepthRange", n)),
+        }
+    }
+
+    pub fn set_depth_range(&self, depth_range: DepthRange) -> ZenseResult<()> {
+        match self.device.backend().set_depth_range(
+            self.device.device_handle,
+            self.session_index,
+            depth_range,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetDepthRange", n)),
+        }
+    }
+
+    pub fn get_threshold(&self) -> ZenseResult<u16> {
+        match self
+            .device
+            .backend()
+            .get_threshold(self.device.device_handle, self.session_index)
+        {
+            Ok(threshold) => Ok(threshold),
+            Err(n) => Err(self.error("Ps2_GetThreshold", n)),
+        }
+    }
+
+    pub fn set_threshold(&self, threshold: u16) -> ZenseResult<()> {
+        match self.device.backend().set_threshold(
+            self.device.device_handle,
+            self.session_index,
+            threshold,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetThreshold", n)),
+        }
+    }
+
+    pub fn get_pulse_count(&self) -> ZenseResult<u16> {
+        match self
+            .device
+            .backend()
+            .get_pulse_count(self.device.device_handle, self.session_index)
+        {
+            Ok(pulse_count) => Ok(pulse_count),
+            Err(n) => Err(self.error("Ps2_GetPulseCount", n)),
+        }
+    }
+
+    pub fn set_pulse_count(&self, pulse_count: u16) -> ZenseResult<()> {
+        match self.device.backend().set_pulse_count(
+            self.device.device_handle,
+            self.session_index,
+            pulse_count,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetPulseCount", n)),
+        }
+    }
+
+    pub fn get_gmm_gain(&self) -> ZenseResult<u16> {
+        match self
+            .device
+            .backend()
+            .get_gmm_gain(self.device.device_handle, self.session_index)
+        {
+            Ok(gmm_gain) => Ok(gmm_gain),
+            Err(n) => Err(self.error("Ps2_GetGMMGain", n)),
+        }
+    }
+
+    pub fn set_gmm_gain(&self, gmm_gain: u16, option: GmmGainEffectiveTime) -> ZenseResult<()> {
+        match self.device.backend().set_gmm_gain(
+            self.device.device_handle,
+            self.session_index,
+            gmm_gain,
+            option,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetGMMGain", n)),
+        }
+    }
+
+    /// Gets a property of the device. `DataMode` is a `DataModeValue`, and `DataModeList` and
+    /// `DepthRangeList` are `Int32ValueList`s; see `supported_data_modes()` and
+    /// `supported_depth_ranges()` for them decoded.
+    pub fn get_property(&self, property_type: PropertyType) -> ZenseResult<PropertyValue> {
+        match self.device.backend().get_property(
+            self.device.device_handle,
+            self.session_index,
+            property_type,
+        ) {
+            Ok(value) => Ok(value),
+            Err(n) => Err(self.error("Ps2_GetProperty", n)),
+        }
+    }
+
+    /// Data modes which the device supports, from `PropertyType::DataModeList`.
+    ///
+    /// Modes unknown to this crate are skipped.
+    pub fn supported_data_modes(&self) -> ZenseResult<Vec<DataMode>> {
+        self.get_int32_list(PropertyType::DataModeList)
+    }
+
+    /// Depth ranges which the device supports, from `PropertyType::DepthRangeList`.
+    ///
+    /// Ranges unknown to this crate are skipped.
+    pub fn supported_depth_ranges(&self) -> ZenseResult<Vec<DepthRange>> {
+        self.get_int32_list(PropertyType::DepthRangeList)
+    }
+
+    fn get_int32_list<T>(&self, property_type: PropertyType) -> ZenseResult<Vec<T>>
+    where
+        T: TryFrom<c_int, Error = UnknownEnumValue>,
+    {
+        match self.get_property(property_type)? {
+            PropertyValue::Int32ValueList(values) => Ok(values
+                .into_iter()
+                .filter_map(|value| match T::try_from(value) {
+                    Ok(value) => Some(value),
+                    Err(e) => {
+                        log::warn!("Skipped value of {:?}: {}", property_type, e);
+                        None
+                    }
+                })
+                .collect()),
+            _ => Err(self.error_in("Ps2_GetProperty", ZenseError::FfiError)),
+        }
+    }
+
+    /// Sets a property of the device.
+    ///
+    /// Fails with `InvalidParams` if `data` is not the variant which `get_property()` returns
+    /// for `property_type`, without calling the SDK.
+    pub fn set_property(
+        &self,
+        property_type: PropertyType,
+        data: PropertyValue,
+    ) -> ZenseResult<()> {
+        if !data.is_value_of(property_type) {
+            return Err(self.error_in("Ps2_SetProperty", ZenseError::InvalidParams));
+        }
+        match self.device.backend().set_property(
+            self.device.device_handle,
+            self.session_index,
+            property_type,
+            data,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetProperty", n)),
+        }
+    }
+
+    /// Sets a property and reads it back, failing with `NoPropertyValueSet` if the device
+    /// reports another value.
+    pub fn set_property_verified(
+        &self,
+        property_type: PropertyType,
+        data: PropertyValue,
+    ) -> ZenseResult<()> {
+        self.set_property(property_type, data.clone())?;
+        if self.get_property(property_type)? == data {
+            Ok(())
+        } else {
+            Err(self.error_in("Ps2_GetProperty", ZenseError::NoPropertyValueSet))
+        }
+    }
+
+    pub fn get_camera_parameters(&self, sensor_type: SensorType) -> ZenseResult<CameraParameters> {
+        match self.device.backend().get_camera_parameters(
+            self.device.device_handle,
+            self.session_index,
+            sensor_type,
+        ) {
+            Ok(camera_parameters) => {
+                let error = 0.001f64;
+                if (camera_parameters.cx - f64::default()).abs() < error
+                    && (camera_parameters.cy - f64::default()).abs() < error
+                    && (camera_parameters.fx - f64::default()).abs() < error
+                    && (camera_parameters.fy - f64::default()).abs() < error
+                    && (camera_parameters.k1 - f64::default()).abs() < error
+                    && (camera_parameters.k2 - f64::default()).abs() < error
+                    && (camera_parameters.k3 - f64::default()).abs() < error
+                    && (camera_parameters.k4 - f64::default()).abs() < error
+                    && (camera_parameters.k5 - f64::default()).abs() < error
+                    && (camera_parameters.k6 - f64::default()).abs() < error
+                    && (camera_parameters.p1 - f64::default()).abs() < error
+                    && (camera_parameters.p2 - f64::default()).abs() < error
+                {
+                    Err(self.error_in("Ps2_GetCameraParameters", ZenseError::FfiError))
+                } else {
+                    Ok(camera_parameters)
+                }
+            }
+            Err(n) => Err(self.error("Ps2_GetCameraParameters", n)),
+        }
+    }
+
+    /// Gets the rotation and translation from the depth sensor to the RGB sensor.
+    pub fn get_camera_extrinsics(&self) -> ZenseResult<CameraExtrinsics> {
+        match self
+            .device
+            .backend()
+            .get_camera_extrinsic_parameters(self.device.device_handle, self.session_index)
+        {
+            Ok(parameters) => Ok(CameraExtrinsics::from(parameters)),
+            Err(n) => Err(self.error("Ps2_GetCameraExtrinsicParameters", n)),
+        }
+    }
+
+    pub fn set_wdr_output_mode(&self, wdr_mode: WdrOutputMode) -> ZenseResult<()> {
+        match self.device.backend().set_wdr_output_mode(
+            self.device.device_handle,
+            self.session_index,
+            wdr_mode,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetWDROutputMode", n)),
+        }
+    }
+
+    pub fn get_wdr_output_mode(&self) -> ZenseResult<WdrOutputMode> {
+        match self
+            .device
+            .backend()
+            .get_wdr_output_mode(self.device.device_handle, self.session_index)
+        {
+            Ok(wdr_mode) => Ok(wdr_mode),
+            Err(n) => Err(self.error("Ps2_GetWDROutputMode", n)),
+        }
+    }
+
+    pub fn set_wdr_style(&self, wdr_style: WdrStyle) -> ZenseResult<()> {
+        match self.device.backend().set_wdr_style(
+            self.device.device_handle,
+            self.session_index,
+            wdr_style,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetWDRStyle", n)),
+        }
+    }
+
+    pub fn set_rgb_frame_enabled(&self, enabled: bool) -> ZenseResult<()> {
+        match self.device.backend().set_rgb_frame_enabled(
+            self.device.device_handle,
+            self.session_index,
+            enabled,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetRgbFrameEnabled", n)),
+        }
+    }
+
+    pub fn set_depth_distortion_correction_enabled(&self, enabled: bool) -> ZenseResult<()> {
+        match self
+            .device
+            .backend()
+            .set_depth_distortion_correction_enabled(
+                self.device.device_handle,
+                self.session_index,
+                enabled,
+            ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetDepthDistortionCorrectionEnabled", n)),
+        }
+    }
+
+    pub fn get_depth_distortion_correction_enabled(&self) -> ZenseResult<bool> {
+        match self
+            .device
+            .backend()
+            .get_depth_distortion_correction_enabled(self.device.device_handle, self.session_index)
+        {
+            Ok(enabled) => Ok(enabled),
+            Err(n) => Err(self.error("Ps2_GetDepthDistortionCorrectionEnabled", n)),
+        }
+    }
+
+    pub fn set_ir_distortion_correction_enabled(&self, enabled: bool) -> ZenseResult<()> {
+        match self.device.backend().set_ir_distortion_correction_enabled(
+            self.device.device_handle,
+            self.session_index,
+            enabled,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetIrDistortionCorrectionEnabled", n)),
+        }
+    }
+
+    pub fn get_ir_distortion_correction_enabled(&self) -> ZenseResult<bool> {
+        match self
+            .device
+            .backend()
+            .get_ir_distortion_correction_enabled(self.device.device_handle, self.session_index)
+        {
+            Ok(enabled) => Ok(enabled),
+            Err(n) => Err(self.error("Ps2_GetIrDistortionCorrectionEnabled", n)),
+        }
+    }
+
+    pub fn set_rgb_distortion_correction_enabled(&self, enabled: bool) -> ZenseResult<()> {
+        match self.device.backend().set_rgb_distortion_correction_enabled(
+            self.device.device_handle,
+            self.session_index,
+            enabled,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetRGBDistortionCorrectionEnabled", n)),
+        }
+    }
+
+    pub fn get_rgb_distortion_correction_enabled(&self) -> ZenseResult<bool> {
+        match self
+            .device
+            .backend()
+            .get_rgb_distortion_correction_enabled(self.device.device_handle, self.session_index)
+        {
+            Ok(enabled) => Ok(enabled),
+            Err(n) => Err(self.error("Ps2_GetRGBDistortionCorrectionEnabled", n)),
+        }
+    }
+
+    pub fn set_compute_real_depth_correction_enabled(&self, enabled: bool) -> ZenseResult<()> {
+        match self
+            .device
+            .backend()
+            .set_compute_real_depth_correction_enabled(
+                self.device.device_handle,
+                self.session_index,
+                enabled,
+            ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetComputeRealDepthCorrectionEnabled", n)),
+        }
+    }
+
+    pub fn get_compute_real_depth_correction_enabled(&self) -> ZenseResult<bool> {
+        match self
+            .device
+            .backend()
+            .get_compute_real_depth_correction_enabled(
+                self.device.device_handle,
+                self.session_index,
+            ) {
+            Ok(enabled) => Ok(enabled),
+            Err(n) => Err(self.error("Ps2_GetComputeRealDepthCorrectionEnabled", n)),
+        }
+    }
+
+    pub fn set_spatial_filter_enabled(&self, enabled: bool) -> ZenseResult<()> {
+        match self.device.backend().set_spatial_filter_enabled(
+            self.device.device_handle,
+            self.session_index,
+            enabled,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetSpatialFilterEnabled", n)),
+        }
+    }
+
+    pub fn get_spatial_filter_enabled(&self) -> ZenseResult<bool> {
+        match self
+            .device
+            .backend()
+            .get_spatial_filter_enabled(self.device.device_handle, self.session_index)
+        {
+            Ok(enabled) => Ok(enabled),
+            Err(n) => Err(self.error("Ps2_GetSpatialFilterEnabled", n)),
+        }
+    }
+
+    pub fn set_time_filter_enabled(&self, enabled: bool) -> ZenseResult<()> {
+        match self.device.backend().set_time_filter_enabled(
+            self.device.device_handle,
+            self.session_index,
+            enabled,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetTimeFilterEnabled", n)),
+        }
+    }
+
+    pub fn get_time_filter_enabled(&self) -> ZenseResult<bool> {
+        match self
+            .device
+            .backend()
+            .get_time_filter_enabled(self.device.device_handle, self.session_index)
+        {
+            Ok(enabled) => Ok(enabled),
+            Err(n) => Err(self.error("Ps2_GetTimeFilterEnabled", n)),
+        }
+    }
+
+    pub fn set_mapper_enabled_rgb_to_depth(&self, enabled: bool) -> ZenseResult<()> {
+        match self.device.backend().set_mapper_enabled_rgb_to_depth(
+            self.device.device_handle,
+            self.session_index,
+            enabled,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetMapperEnabledRGBToDepth", n)),
+        }
+    }
+
+    pub fn get_mapper_enabled_rgb_to_depth(&self) -> ZenseResult<bool> {
+        match self
+            .device
+            .backend()
+            .get_mapper_enabled_rgb_to_depth(self.device.device_handle, self.session_index)
+        {
+            Ok(enabled) => Ok(enabled),
+            Err(n) => Err(self.error("Ps2_GetMapperEnabledRGBToDepth", n)),
+        }
+    }
+
+    pub fn set_mapper_enabled_depth_to_rgb(&self, enabled: bool) -> ZenseResult<()> {
+        match self.device.backend().set_mapper_enabled_depth_to_rgb(
+            self.device.device_handle,
+            self.session_index,
+            enabled,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.error("Ps2_SetMapperEnabledDepthToRGB", n)),
+        }
+    }
+
+    pub fn get_mapper_enabled_depth_to_rgb(&self) -> ZenseResult<bool> {
+        match self
+            .device
+            .backend()
+            .get_mapper_enabled_depth_to_rgb(self.device.device_handle, self.session_index)
+        {
+            Ok(enabled) => Ok(enabled),
+            Err(n) => Err(self.error("Ps2_GetMapperEnabledDepthToRGB", n)),
+        }
+    }
+
+    pub fn set_rgb_resolution(&self, resolution: Resolution) -> ZenseResult<()> {
+        match self.device.backend().set_rgb_resolution(
+            self.device.device_handle,
+            self.session_index,
+            resolution,
+        ) {
+            Ok(()) => {
+                self.last_set().rgb_resolution = Some(resolution);
+                Ok(())
+            }
+            Err(n) => Err(self.error("Ps2_SetRGBResolution", n)),
+        }
+    }
+
+    /// The RGB resolution last set by `set_rgb_resolution()` through this device handle, as the
+    /// SDK cannot report it. `None` until it was set.
+    pub fn last_set_rgb_resolution(&self) -> Option<Resolution> {
+        self.last_set().rgb_resolution
+    }
+
+    pub fn set_color_pixel_format(&self, pixel_format: PixelFormat) -> ZenseResult<()> {
+        match self.device.backend().set_color_pixel_format(
+            self.device.device_handle,
+            self.session_index,
+            pixel_format,
+        ) {
+            Ok(()) => {
+                self.last_set().color_pixel_format = Some(pixel_format);
+                Ok(())
+            }
+            Err(n) => Err(self.error("Ps2_SetColorPixelFormat", n)),
+        }
+    }
+
+    /// The color pixel format last set by `set_color_pixel_format()` through this device
+    /// handle, as the SDK cannot report it. `None` until it was set.
+    pub fn last_set_color_pixel_format(&self) -> Option<PixelFormat> {
+        self.last_set().color_pixel_format
+    }
+
+    /// Converts pixels of depth frames, with their depth in millimeters, to points in the
+    /// camera space in millimeters with the calibration of the device.
+    pub fn convert_depth_to_world(
+        &self,
+        depth_vectors: &[DepthVector3],
+    ) -> ZenseResult<Vec<[f32; 3]>> {
+        match self.device.backend().convert_depth_to_world(
+            self.device.device_handle,
+            self.session_index,
+            depth_vectors,
+        ) {
+            Ok(world_vectors) => Ok(world_vectors.iter().map(|v| [v.x, v.y, v.z]).collect()),
+            Err(n) => Err(self.error("Ps2_ConvertDepthToWorld", n)),
+        }
+    }
+
+    /// Projects points in the camera space in millimeters onto depth frames.
+    pub fn convert_world_to_depth(
+        &self,
+        world_vectors: &[[f32; 3]],
+    ) -> ZenseResult<Vec<DepthVector3>> {
+        let world_vectors = world_vectors
+            .iter()
+            .map(|&[x, y, z]| PsVector3f { x, y, z })
+            .collect::<Vec<_>>();
+        match self.device.backend().convert_world_to_depth(
+            self.device.device_handle,
+            self.session_index,
+            &world_vectors,
+        ) {
+            Ok(depth_vectors) => Ok(depth_vectors),
+            Err(n) => Err(self.error("Ps2_ConvertWorldToDepth", n)),
+        }
+    }
+
+    /// Converts every pixel of `depth_frame`, row by row, to a point in the camera space in
+    /// millimeters. Pixels without depth give the origin.
+    pub fn convert_depth_frame_to_world(
+        &self,
+        depth_frame: &DepthFrame,
+    ) -> ZenseResult<Vec<[f32; 3]>> {
+        let frame = depth_frame.frame();
+        let ps_frame = PsFrame {
+            frame_index: frame.frame_index,
+            frame_type: frame.frame_type,
+            pixel_format: frame.pixel_format,
+            imu_frame_no: frame.imu_frame_no,
+            // only read by the SDK
+            frame_data: frame.frame_data.as_ptr() as *mut u8,
+            data_len: frame.frame_data.len() as u32,
+            exposure_time: frame.exposure_time,
+            depth_range: frame.depth_range,
+            width: frame.width,
+            height: frame.height,
+        };
+        // the data of `DepthFrame` is validated to be `width * height` pixels long
+        match unsafe {
+            self.device.backend().convert_depth_frame_to_world_vector(
+                self.device.device_handle,
+                self.session_index,
+                &ps_frame,
+            )
+        } {
+            Ok(world_vectors) => Ok(world_vectors.iter().map(|v| [v.x, v.y, v.z]).collect()),
+            Err(n) => Err(self.error("Ps2_ConvertDepthFrameToWorldVector", n)),
+        }
+    }
+
+    fn last_set(&self) -> RefMut<'_, LastSet> {
+        RefMut::map(self.device.last_set.borrow_mut(), |last_set| {
+            &mut last_set[self.session_index as usize]
+        })
+    }
+}
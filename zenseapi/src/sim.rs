@@ -0,0 +1,1301 @@
+//! A simulated DCAM710 which renders procedural scenes, for running without a camera.
+//!
+//! ```no_run
+//! use zenseapi::sim::SimBackend;
+//! use zenseapi::Zense;
+//!
+//! let zense = Zense::with_backend(SimBackend::default()).unwrap();
+//! let device = zense.open_device("sim://dcam710/0").unwrap();
+//! ```
+
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use raw::enums::{
+    GmmGainEffectiveTime, PropertyValue, PsDataMode, PsDepthRange, PsDeviceType, PsFrameType,
+    PsPixelFormat, PsPropertyType, PsResolution, PsSensorType, PsWdrStyle, PsWdrTotalRange,
+};
+use raw::types::{
+    PsCameraExtrinsicParameters, PsCameraParameters, PsDepthVector3, PsDeviceHandle, PsDeviceInfo,
+    PsFrame, PsFrameReady, PsReturnStatus, PsVector3f, PsWdrOutputMode,
+};
+use zenseapi_sys as raw;
+
+use crate::Backend;
+
+pub use self::scene::*;
+
+mod scene;
+
+const FPS: f64 = 30.0;
+const DEPTH_WIDTH: usize = 640;
+const DEPTH_HEIGHT: usize = 480;
+/// Focal length of both cameras relative to the image width.
+const FOCAL_RATIO: f64 = 0.718_75;
+/// Position of the RGB camera in the depth camera frame, in millimeters.
+const RGB_ORIGIN: Vec3 = [25.0, 0.0, 0.0];
+const MIN_DEPTH_MM: f64 = 350.0;
+const DEFAULT_THRESHOLD: u16 = 20;
+const DEFAULT_PULSE_COUNT: u16 = 100;
+const DEFAULT_GMM_GAIN: u16 = 100;
+/// IR amplitude of a fully reflective surface facing the camera at one meter.
+const IR_GAIN: f64 = 2000.0;
+
+const NO_DEVICE_CONNECTED: PsReturnStatus = -1;
+const INVALID_DEVICE_INDEX: PsReturnStatus = -2;
+const DEVICE_POINTER_IS_NULL: PsReturnStatus = -3;
+const INVALID_FRAME_TYPE: PsReturnStatus = -4;
+const NO_PROPERTY_VALUE_SET: PsReturnStatus = -7;
+const INVALID_DEPTH_RANGE: PsReturnStatus = -10;
+const READ_NEXT_FRAME_ERROR: PsReturnStatus = -11;
+const CAMERA_NOT_OPENED: PsReturnStatus = -13;
+const INVALID_PARAMS: PsReturnStatus = -15;
+
+const DATA_MODES: [PsDataMode; 8] = [
+    PsDataMode::DepthAndRgb30Fps,
+    PsDataMode::IrAndRGB30Fps,
+    PsDataMode::DepthAndIr30Fps,
+    PsDataMode::NoCcd30Fps,
+    PsDataMode::DepthAndIr15FpsRgb30Fps,
+    PsDataMode::WdrDepth,
+    PsDataMode::WdrIr,
+    PsDataMode::WdrDepthAndIr,
+];
+
+const DEPTH_RANGES: [PsDepthRange; 9] = [
+    PsDepthRange::NearRange,
+    PsDepthRange::MidRange,
+    PsDepthRange::FarRange,
+    PsDepthRange::XNearRange,
+    PsDepthRange::XMidRange,
+    PsDepthRange::XFarRange,
+    PsDepthRange::XxNearRange,
+    PsDepthRange::XxMidRange,
+    PsDepthRange::XxFarRange,
+];
+
+/// Configuration of a `SimBackend`.
+#[derive(Clone, Debug)]
+pub struct SimConfig {
+    /// Number of devices enumerated, at `sim://dcam710/0` onwards.
+    pub device_count: u32,
+    /// Seed of the noise. A device renders the same frames for the same seed and calls.
+    pub seed: u64,
+    pub scene: Scene,
+    /// Whether `read_next_frame()` waits for the frame rate of the data mode, instead of
+    /// returning immediately. Motion follows the frame count either way.
+    pub realtime: bool,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        SimConfig {
+            device_count: 1,
+            seed: 0,
+            scene: Scene::default(),
+            realtime: false,
+        }
+    }
+}
+
+/// A `Backend` simulating DCAM710 devices, for `Zense::with_backend()`.
+///
+/// Depth has noise growing with the distance, and pixels are invalid, that is 0, out of the
+/// depth range, below the threshold, at grazing angles and at random. The mapped frames are
+/// rendered from the other camera, whose parameters `get_camera_parameters()` and
+/// `get_camera_extrinsic_parameters()` return.
+#[derive(Debug)]
+pub struct SimBackend {
+    config: SimConfig,
+    devices: Mutex<Vec<SimDevice>>,
+}
+
+impl SimBackend {
+    pub fn new(config: SimConfig) -> Self {
+        let devices = (0..config.device_count)
+            .map(|index| SimDevice::new(index, config.seed))
+            .collect();
+        SimBackend {
+            config,
+            devices: Mutex::new(devices),
+        }
+    }
+
+    pub fn config(&self) -> &SimConfig {
+        &self.config
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<SimDevice>> {
+        // a panic while rendering leaves nothing half updated which matters
+        self.devices.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Runs `f` on the opened device of `device_handle`, after checking `session_index`.
+    fn with_device<T>(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        f: impl FnOnce(&mut SimDevice) -> Result<T, PsReturnStatus>,
+    ) -> Result<T, PsReturnStatus> {
+        let mut devices = self.lock();
+        let device = device_of(&mut devices, device_handle)?;
+        if session_index != 0 {
+            return Err(INVALID_PARAMS);
+        }
+        f(device)
+    }
+}
+
+impl Default for SimBackend {
+    fn default() -> Self {
+        SimBackend::new(SimConfig::default())
+    }
+}
+
+fn device_of(
+    devices: &mut [SimDevice],
+    device_handle: PsDeviceHandle,
+) -> Result<&mut SimDevice, PsReturnStatus> {
+    let index = (device_handle as usize).wrapping_sub(1);
+    match devices.get_mut(index) {
+        Some(device) if device.opened => Ok(device),
+        Some(_) => Err(CAMERA_NOT_OPENED),
+        None => Err(DEVICE_POINTER_IS_NULL),
+    }
+}
+
+fn uri_of(index: u32) -> String {
+    format!("sim://dcam710/{}", index)
+}
+
+fn copy_to_c_chars(s: &str, c_chars: &mut [c_char]) {
+    let len = s.len().min(c_chars.len() - 1);
+    for (c_char, &byte) in c_chars.iter_mut().zip(&s.as_bytes()[..len]) {
+        *c_char = byte as c_char;
+    }
+}
+
+// SAFETY: frame buffers are only replaced by `render()` on a read, and dropped on a stop or close.
+unsafe impl Backend for SimBackend {
+    fn initialize(&self) -> Result<(), PsReturnStatus> {
+        Ok(())
+    }
+
+    fn shutdown(&self) -> Result<(), PsReturnStatus> {
+        for device in self.lock().iter_mut() {
+            device.close();
+        }
+        Ok(())
+    }
+
+    fn get_device_count(&self) -> Result<u32, PsReturnStatus> {
+        Ok(self.config.device_count)
+    }
+
+    fn get_device_list_info(&self, device_count: u32) -> Result<Vec<PsDeviceInfo>, PsReturnStatus> {
+        if device_count > self.config.device_count {
+            return Err(INVALID_PARAMS);
+        }
+        (0..device_count)
+            .map(|index| self.get_device_info(index))
+            .collect()
+    }
+
+    fn get_device_info(&self, device_index: u32) -> Result<PsDeviceInfo, PsReturnStatus> {
+        let devices = self.lock();
+        let device = devices
+            .get(device_index as usize)
+            .ok_or(INVALID_DEVICE_INDEX)?;
+        let mut device_info = PsDeviceInfo {
+            session_count: 1,
+            device_type: PsDeviceType::Dcam710 as i32,
+            uri: [0; 256usize],
+            fw: [0; 50usize],
+            // PsConnectStatus::Connected or Opened
+            status: if device.opened { 3 } else { 2 },
+        };
+        copy_to_c_chars(&uri_of(device_index), &mut device_info.uri);
+        copy_to_c_chars(FIRMWARE_VERSION, &mut device_info.fw);
+        Ok(device_info)
+    }
+
+    fn open_device(&self, uri: &str) -> Result<PsDeviceHandle, PsReturnStatus> {
+        let mut devices = self.lock();
+        if devices.is_empty() {
+            return Err(NO_DEVICE_CONNECTED);
+        }
+        let index = (0..self.config.device_count)
+            .find(|&index| uri_of(index) == uri)
+            .ok_or(INVALID_DEVICE_INDEX)?;
+        let device = &mut devices[index as usize];
+        if device.opened {
+            return Err(INVALID_PARAMS);
+        }
+        device.opened = true;
+        Ok((index as usize + 1) as PsDeviceHandle)
+    }
+
+    fn close_device(&self, device_handle: PsDeviceHandle) -> Result<(), PsReturnStatus> {
+        let mut devices = self.lock();
+        device_of(&mut devices, device_handle)?.close();
+        Ok(())
+    }
+
+    fn start_stream(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.stream = Some(StreamState {
+                started_at: Instant::now(),
+                read_count: 0,
+            });
+            device.frames.clear();
+            Ok(())
+        })
+    }
+
+    fn stop_stream(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.stream = None;
+            device.frames.clear();
+            Ok(())
+        })
+    }
+
+    fn read_next_frame(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsFrameReady, PsReturnStatus> {
+        if self.config.realtime {
+            // wait outside the lock so that other calls go on meanwhile
+            let due = self.with_device(device_handle, session_index, |device| {
+                let stream = device.stream.as_ref().ok_or(READ_NEXT_FRAME_ERROR)?;
+                let period = 1.0 / FPS;
+                Ok(stream.started_at + Duration::from_secs_f64(stream.read_count as f64 * period))
+            })?;
+            let now = Instant::now();
+            if due > now {
+                std::thread::sleep(due - now);
+            }
+        }
+        let scene = &self.config.scene;
+        self.with_device(device_handle, session_index, |device| device.render(scene))
+    }
+
+    fn get_frame(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        frame_type: PsFrameType,
+    ) -> Result<PsFrame, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            let frame = device
+                .frames
+                .get_mut(&(frame_type as i32))
+                .ok_or(INVALID_FRAME_TYPE)?;
+            Ok(PsFrame {
+                frame_index: frame.frame_index,
+                frame_type,
+                pixel_format: frame.pixel_format,
+                imu_frame_no: 0,
+                // the buffer is only replaced by the next read or cleared by stopping
+                frame_data: frame.data.as_mut_ptr(),
+                data_len: frame.data.len() as u32,
+                exposure_time: frame.exposure_time,
+                depth_range: frame.depth_range,
+                width: frame.width as u16,
+                height: frame.height as u16,
+            })
+        })
+    }
+
+    fn set_data_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        data_mode: PsDataMode,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.data_mode = data_mode;
+            Ok(())
+        })
+    }
+
+    fn get_data_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsDataMode, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| Ok(device.data_mode))
+    }
+
+    fn get_depth_range(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsDepthRange, PsReturnStatus> {
+        self.with_device(
+            device_handle,
+            session_index,
+            |device| Ok(device.depth_range),
+        )
+    }
+
+    fn set_depth_range(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_range: PsDepthRange,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            if depth_range == PsDepthRange::Unknown {
+                return Err(INVALID_DEPTH_RANGE);
+            }
+            device.depth_range = depth_range;
+            Ok(())
+        })
+    }
+
+    fn get_threshold(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<u16, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| Ok(device.threshold))
+    }
+
+    fn set_threshold(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        threshold: u16,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.threshold = threshold;
+            Ok(())
+        })
+    }
+
+    fn get_pulse_count(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<u16, PsReturnStatus> {
+        self.with_device(
+            device_handle,
+            session_index,
+            |device| Ok(device.pulse_count),
+        )
+    }
+
+    fn set_pulse_count(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        pulse_count: u16,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.pulse_count = pulse_count;
+            Ok(())
+        })
+    }
+
+    fn get_gmm_gain(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<u16, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| Ok(device.gmm_gain))
+    }
+
+    fn set_gmm_gain(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        gmm_gain: u16,
+        _option: GmmGainEffectiveTime,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.gmm_gain = gmm_gain;
+            Ok(())
+        })
+    }
+
+    fn get_property(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        property_type: PsPropertyType,
+    ) -> Result<PropertyValue, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            let string_value = |s: String| PropertyValue::StringValue(CString::new(s).unwrap());
+            Ok(match property_type {
+                PsPropertyType::SerialNumber => string_value(format!("SIM710{:06}", device.index)),
+                PsPropertyType::FirmwareVersion => string_value(FIRMWARE_VERSION.to_string()),
+                PsPropertyType::HardwareVersion => string_value(HARDWARE_VERSION.to_string()),
+                PsPropertyType::DataMode => PropertyValue::DataModeValue(device.data_mode),
+                PsPropertyType::DataModeList => PropertyValue::Int32ValueList(
+                    DATA_MODES.iter().map(|&mode| mode as i32).collect(),
+                ),
+                PsPropertyType::DepthRangeList => PropertyValue::Int32ValueList(
+                    DEPTH_RANGES.iter().map(|&range| range as i32).collect(),
+                ),
+            })
+        })
+    }
+
+    fn set_property(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        property_type: PsPropertyType,
+        data: PropertyValue,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            match (property_type, data) {
+                (PsPropertyType::DataMode, PropertyValue::DataModeValue(data_mode)) => {
+                    if !DATA_MODES.contains(&data_mode) {
+                        return Err(INVALID_PARAMS);
+                    }
+                    device.data_mode = data_mode;
+                    Ok(())
+                }
+                (PsPropertyType::DataMode, _) => Err(INVALID_PARAMS),
+                _ => Err(NO_PROPERTY_VALUE_SET),
+            }
+        })
+    }
+
+    fn get_camera_parameters(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        sensor_type: PsSensorType,
+    ) -> Result<PsCameraParameters, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            let camera = match sensor_type {
+                PsSensorType::DepthSensor => Camera::depth(),
+                PsSensorType::RgbSensor => Camera::rgb(device.rgb_resolution),
+            };
+            Ok(camera.parameters())
+        })
+    }
+
+    fn get_camera_extrinsic_parameters(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsCameraExtrinsicParameters, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |_| {
+            // the cameras are parallel, so a point only shifts by the offset of the RGB camera
+            Ok(PsCameraExtrinsicParameters {
+                rotation: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+                translation: [-RGB_ORIGIN[0], -RGB_ORIGIN[1], -RGB_ORIGIN[2]],
+            })
+        })
+    }
+
+    fn set_wdr_output_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        wdr_mode: PsWdrOutputMode,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.wdr_output_mode = wdr_mode;
+            Ok(())
+        })
+    }
+
+    fn get_wdr_output_mode(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<PsWdrOutputMode, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            Ok(device.wdr_output_mode)
+        })
+    }
+
+    fn set_wdr_style(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        wdr_style: PsWdrStyle,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.wdr_style = wdr_style;
+            Ok(())
+        })
+    }
+
+    fn set_rgb_frame_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.rgb_frame_enabled = enabled;
+            Ok(())
+        })
+    }
+
+    fn set_depth_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.depth_distortion_correction_enabled = enabled;
+            Ok(())
+        })
+    }
+
+    fn get_depth_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            Ok(device.depth_distortion_correction_enabled)
+        })
+    }
+
+    fn set_ir_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.ir_distortion_correction_enabled = enabled;
+            Ok(())
+        })
+    }
+
+    fn get_ir_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            Ok(device.ir_distortion_correction_enabled)
+        })
+    }
+
+    fn set_rgb_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.rgb_distortion_correction_enabled = enabled;
+            Ok(())
+        })
+    }
+
+    fn get_rgb_distortion_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            Ok(device.rgb_distortion_correction_enabled)
+        })
+    }
+
+    fn set_compute_real_depth_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.compute_real_depth_correction_enabled = enabled;
+            Ok(())
+        })
+    }
+
+    fn get_compute_real_depth_correction_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            Ok(device.compute_real_depth_correction_enabled)
+        })
+    }
+
+    fn set_spatial_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.spatial_filter_enabled = enabled;
+            Ok(())
+        })
+    }
+
+    fn get_spatial_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            Ok(device.spatial_filter_enabled)
+        })
+    }
+
+    fn set_time_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.time_filter_enabled = enabled;
+            Ok(())
+        })
+    }
+
+    fn get_time_filter_enabled(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            Ok(device.time_filter_enabled)
+        })
+    }
+
+    fn set_mapper_enabled_rgb_to_depth(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.mapper_rgb_to_depth_enabled = enabled;
+            Ok(())
+        })
+    }
+
+    fn get_mapper_enabled_rgb_to_depth(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            Ok(device.mapper_rgb_to_depth_enabled)
+        })
+    }
+
+    fn set_mapper_enabled_depth_to_rgb(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        enabled: bool,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.mapper_depth_to_rgb_enabled = enabled;
+            Ok(())
+        })
+    }
+
+    fn get_mapper_enabled_depth_to_rgb(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+    ) -> Result<bool, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            Ok(device.mapper_depth_to_rgb_enabled)
+        })
+    }
+
+    fn set_rgb_resolution(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        resolution: PsResolution,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            device.rgb_resolution = resolution;
+            Ok(())
+        })
+    }
+
+    fn set_color_pixel_format(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        pixel_format: PsPixelFormat,
+    ) -> Result<(), PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| match pixel_format {
+            PsPixelFormat::Rgb888 | PsPixelFormat::Bgr888 => {
+                device.color_pixel_format = pixel_format;
+                Ok(())
+            }
+            _ => Err(INVALID_PARAMS),
+        })
+    }
+
+    fn convert_depth_to_world(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_vectors: &[PsDepthVector3],
+    ) -> Result<Vec<PsVector3f>, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |_| {
+            let camera = Camera::depth();
+            Ok(depth_vectors
+                .iter()
+                .map(|v| camera.unproject(f64::from(v.depth_x), f64::from(v.depth_y), v.depth_z))
+                .collect())
+        })
+    }
+
+    fn convert_world_to_depth(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        world_vectors: &[PsVector3f],
+    ) -> Result<Vec<PsDepthVector3>, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |_| {
+            let camera = Camera::depth();
+            Ok(world_vectors.iter().map(|&v| camera.project(v)).collect())
+        })
+    }
+
+    unsafe fn convert_depth_frame_to_world_vector(
+        &self,
+        device_handle: PsDeviceHandle,
+        session_index: u32,
+        depth_frame: &PsFrame,
+    ) -> Result<Vec<PsVector3f>, PsReturnStatus> {
+        self.with_device(device_handle, session_index, |device| {
+            // mapped depth is seen from the RGB camera
+            let camera = match depth_frame.frame_type {
+                PsFrameType::MappedDepthFrame => Camera::rgb(device.rgb_resolution),
+                _ => Camera::depth(),
+            };
+            let width = usize::from(depth_frame.width);
+            let len = width * usize::from(depth_frame.height) * 2;
+            if depth_frame.frame_data.is_null() || (depth_frame.data_len as usize) < len {
+                return Err(INVALID_PARAMS);
+            }
+            let data = std::slice::from_raw_parts(depth_frame.frame_data, len);
+            Ok(data
+                .chunks_exact(2)
+                .enumerate()
+                .map(|(i, bytes)| {
+                    let depth = u16::from_ne_bytes([bytes[0], bytes[1]]);
+                    camera.unproject((i % width) as f64, (i / width) as f64, depth)
+                })
+                .collect())
+        })
+    }
+}
+
+const FIRMWARE_VERSION: &str = "DCAM710_sim_1.0.0";
+const HARDWARE_VERSION: &str = "sim";
+
+#[derive(Debug)]
+struct StreamState {
+    started_at: Instant,
+    read_count: u64,
+}
+
+#[derive(Debug)]
+struct SimFrame {
+    frame_type: PsFrameType,
+    frame_index: u32,
+    pixel_format: PsPixelFormat,
+    data: Vec<u8>,
+    exposure_time: f32,
+    depth_range: PsDepthRange,
+    width: usize,
+    height: usize,
+}
+
+struct SimDevice {
+    index: u32,
+    opened: bool,
+    rng: Rng,
+    stream: Option<StreamState>,
+    // keyed by PsFrameType as i32, holding the frames of the last read
+    frames: HashMap<i32, SimFrame>,
+    frame_indices: HashMap<i32, u32>,
+    data_mode: PsDataMode,
+    depth_range: PsDepthRange,
+    threshold: u16,
+    pulse_count: u16,
+    gmm_gain: u16,
+    wdr_output_mode: PsWdrOutputMode,
+    wdr_style: PsWdrStyle,
+    rgb_frame_enabled: bool,
+    rgb_resolution: PsResolution,
+    color_pixel_format: PsPixelFormat,
+    depth_distortion_correction_enabled: bool,
+    ir_distortion_correction_enabled: bool,
+    rgb_distortion_correction_enabled: bool,
+    compute_real_depth_correction_enabled: bool,
+    spatial_filter_enabled: bool,
+    time_filter_enabled: bool,
+    mapper_rgb_to_depth_enabled: bool,
+    mapper_depth_to_rgb_enabled: bool,
+}
+
+// PsWdrOutputMode and GmmGainEffectiveTime do not implement Debug
+impl std::fmt::Debug for SimDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SimDevice")
+            .field("index", &self.index)
+            .field("opened", &self.opened)
+            .field("stream", &self.stream)
+            .field("data_mode", &self.data_mode)
+            .field("depth_range", &self.depth_range)
+            .field("rgb_resolution", &self.rgb_resolution)
+            .finish()
+    }
+}
+
+impl SimDevice {
+    fn new(index: u32, seed: u64) -> Self {
+        SimDevice {
+            index,
+            opened: false,
+            rng: Rng::new(seed ^ u64::from(index + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15)),
+            stream: None,
+            frames: HashMap::new(),
+            frame_indices: HashMap::new(),
+            data_mode: PsDataMode::DepthAndRgb30Fps,
+            depth_range: PsDepthRange::NearRange,
+            threshold: DEFAULT_THRESHOLD,
+            pulse_count: DEFAULT_PULSE_COUNT,
+            gmm_gain: DEFAULT_GMM_GAIN,
+            wdr_output_mode: PsWdrOutputMode {
+                total_range: PsWdrTotalRange::WdrTotalRangeTwo,
+                range1: PsDepthRange::NearRange,
+                range1_count: 1,
+                range2: PsDepthRange::FarRange,
+                range2_count: 1,
+                range3: PsDepthRange::FarRange,
+                range3_count: 0,
+            },
+            wdr_style: PsWdrStyle::Fusion,
+            rgb_frame_enabled: true,
+            rgb_resolution: PsResolution::Res640x480,
+            color_pixel_format: PsPixelFormat::Bgr888,
+            depth_distortion_correction_enabled: true,
+            ir_distortion_correction_enabled: true,
+            rgb_distortion_correction_enabled: true,
+            compute_real_depth_correction_enabled: true,
+            spatial_filter_enabled: false,
+            time_filter_enabled: false,
+            mapper_rgb_to_depth_enabled: false,
+            mapper_depth_to_rgb_enabled: false,
+        }
+    }
+
+    fn close(&mut self) {
+        self.opened = false;
+        self.stream = None;
+        self.frames.clear();
+    }
+
+    /// Depth ranges which the WDR modes cycle through.
+    fn wdr_ranges(&self) -> Vec<PsDepthRange> {
+        let mode = self.wdr_output_mode;
+        let mut ranges = vec![mode.range1, mode.range2];
+        let total_range = mode.total_range;
+        if total_range == PsWdrTotalRange::WdrTotalRangeThree {
+            ranges.push(mode.range3);
+        }
+        ranges
+    }
+
+    /// Renders the frames of the next read and returns which are ready.
+    fn render(&mut self, scene: &Scene) -> Result<PsFrameReady, PsReturnStatus> {
+        let read_count = match &mut self.stream {
+            Some(stream) => {
+                stream.read_count += 1;
+                stream.read_count - 1
+            }
+            None => return Err(READ_NEXT_FRAME_ERROR),
+        };
+        let time = read_count as f64 / FPS;
+        self.frames.clear();
+
+        let (depth, ir, rgb) = match self.data_mode {
+            PsDataMode::DepthAndRgb30Fps => (true, false, true),
+            PsDataMode::IrAndRGB30Fps => (false, true, true),
+            PsDataMode::DepthAndIr30Fps => (true, true, false),
+            PsDataMode::NoCcd30Fps => (false, false, true),
+            PsDataMode::DepthAndIr15FpsRgb30Fps => {
+                let tof = read_count % 2 == 0;
+                (tof, tof, true)
+            }
+            PsDataMode::WdrDepth => (true, false, false),
+            PsDataMode::WdrIr => (false, true, false),
+            PsDataMode::WdrDepthAndIr => (true, true, false),
+        };
+        let rgb = rgb && self.rgb_frame_enabled;
+        let wdr = matches!(
+            self.data_mode,
+            PsDataMode::WdrDepth | PsDataMode::WdrIr | PsDataMode::WdrDepthAndIr
+        );
+        let depth_range = if wdr {
+            let ranges = self.wdr_ranges();
+            match self.wdr_style {
+                PsWdrStyle::Fusion => ranges
+                    .into_iter()
+                    .max_by(|a, b| max_depth_mm(*a).partial_cmp(&max_depth_mm(*b)).unwrap())
+                    .unwrap(),
+                PsWdrStyle::Alternation => ranges[read_count as usize % ranges.len()],
+            }
+        } else {
+            self.depth_range
+        };
+
+        let depth_camera = Camera::depth();
+        let rgb_camera = Camera::rgb(self.rgb_resolution);
+        if depth || ir {
+            let tof = self.render_tof(scene, &depth_camera, depth_range, time);
+            if depth {
+                let depth_type = if wdr && self.wdr_style == PsWdrStyle::Fusion {
+                    PsFrameType::WdrDepthFrame
+                } else {
+                    PsFrameType::DepthFrame
+                };
+                self.store(depth_type, &depth_camera, depth_range, tof.depth);
+                self.store(
+                    PsFrameType::ConfidenceFrame,
+                    &depth_camera,
+                    depth_range,
+                    tof.confidence,
+                );
+            }
+            if ir {
+                self.store(PsFrameType::IrFrame, &depth_camera, depth_range, tof.ir);
+            }
+            if self.mapper_depth_to_rgb_enabled && !wdr {
+                let mapped = self.render_tof(scene, &rgb_camera, depth_range, time);
+                if depth {
+                    self.store(
+                        PsFrameType::MappedDepthFrame,
+                        &rgb_camera,
+                        depth_range,
+                        mapped.depth,
+                    );
+                }
+                if ir {
+                    self.store(
+                        PsFrameType::MappedIrFrame,
+                        &rgb_camera,
+                        depth_range,
+                        mapped.ir,
+                    );
+                }
+            }
+        }
+        if rgb {
+            let color = self.render_color(scene, &rgb_camera, time);
+            self.store(PsFrameType::RgbFrame, &rgb_camera, depth_range, color);
+            if self.mapper_rgb_to_depth_enabled {
+                let mapped = self.render_color(scene, &depth_camera, time);
+                self.store(
+                    PsFrameType::MappedRgbFrame,
+                    &depth_camera,
+                    depth_range,
+                    mapped,
+                );
+            }
+        }
+
+        Ok(self
+            .frames
+            .values()
+            .map(|frame| ready_bit(frame.frame_type))
+            .fold(0, |ready, bit| ready | bit))
+    }
+
+    fn store(
+        &mut self,
+        frame_type: PsFrameType,
+        camera: &Camera,
+        depth_range: PsDepthRange,
+        (pixel_format, data): (PsPixelFormat, Vec<u8>),
+    ) {
+        let frame_index = self.frame_indices.entry(frame_type as i32).or_insert(0);
+        let frame = SimFrame {
+            frame_type,
+            frame_index: *frame_index,
+            pixel_format,
+            data,
+            exposure_time: f32::from(self.pulse_count) * 0.01,
+            depth_range,
+            width: camera.width,
+            height: camera.height,
+        };
+        *frame_index = frame_index.wrapping_add(1);
+        self.frames.insert(frame_type as i32, frame);
+    }
+
+    fn render_tof(
+        &mut self,
+        scene: &Scene,
+        camera: &Camera,
+        depth_range: PsDepthRange,
+        time: f64,
+    ) -> TofImages {
+        let pixel_count = camera.width * camera.height;
+        let mut depth = Vec::with_capacity(pixel_count * 2);
+        let mut ir = Vec::with_capacity(pixel_count * 2);
+        let mut confidence = Vec::with_capacity(pixel_count * 2);
+        let max_depth = max_depth_mm(depth_range);
+        let gain = f64::from(self.pulse_count) / f64::from(DEFAULT_PULSE_COUNT)
+            * f64::from(self.gmm_gain)
+            / f64::from(DEFAULT_GMM_GAIN);
+        let mut noise_scale = 1.0;
+        if self.spatial_filter_enabled {
+            noise_scale *= 0.5;
+        }
+        if self.time_filter_enabled {
+            noise_scale *= 0.5;
+        }
+        for y in 0..camera.height {
+            for x in 0..camera.width {
+                let (nx, ny) = camera.normalized(x, y);
+                let (z, amplitude) = match scene.cast(camera.origin, nx, ny, time) {
+                    Some(hit) => {
+                        let meters = hit.z / 1000.0;
+                        let amplitude = IR_GAIN * hit.reflectivity * hit.cos_incidence * gain
+                            / (meters * meters);
+                        let amplitude = amplitude * (1.0 + 0.02 * self.rng.gaussian());
+                        let sigma = (1.0 + hit.z * hit.z * 1e-6) * noise_scale;
+                        let z = hit.z + sigma * self.rng.gaussian();
+                        let valid = z >= MIN_DEPTH_MM
+                            && z <= max_depth
+                            && amplitude >= f64::from(self.threshold)
+                            && hit.cos_incidence >= 0.1
+                            && self.rng.uniform() >= 0.002;
+                        (if valid { z } else { 0.0 }, amplitude.max(0.0))
+                    }
+                    None => (0.0, 0.0),
+                };
+                let confidence_value = if z > 0.0 {
+                    (amplitude / (amplitude + 100.0) * 65535.0).round()
+                } else {
+                    0.0
+                };
+                depth.extend_from_slice(&(z.round() as u16).to_ne_bytes());
+                ir.extend_from_slice(&(amplitude.round().min(65535.0) as u16).to_ne_bytes());
+                confidence.extend_from_slice(&(confidence_value as u16).to_ne_bytes());
+            }
+        }
+        TofImages {
+            depth: (PsPixelFormat::DepthMm16, depth),
+            ir: (PsPixelFormat::Gray16, ir),
+            confidence: (PsPixelFormat::Gray16, confidence),
+        }
+    }
+
+    fn render_color(
+        &mut self,
+        scene: &Scene,
+        camera: &Camera,
+        time: f64,
+    ) -> (PsPixelFormat, Vec<u8>) {
+        let mut data = Vec::with_capacity(camera.width * camera.height * 3);
+        for y in 0..camera.height {
+            for x in 0..camera.width {
+                let (nx, ny) = camera.normalized(x, y);
+                let mut rgb = [0u8; 3];
+                if let Some(hit) = scene.cast(camera.origin, nx, ny, time) {
+                    let shade = 0.3 + 0.7 * hit.cos_incidence;
+                    for (channel, &color) in rgb.iter_mut().zip(&hit.color) {
+                        let value = f64::from(color) * shade + 2.0 * self.rng.gaussian();
+                        *channel = value.round().clamp(0.0, 255.0) as u8;
+                    }
+                }
+                if self.color_pixel_format == PsPixelFormat::Bgr888 {
+                    rgb.reverse();
+                }
+                data.extend_from_slice(&rgb);
+            }
+        }
+        (self.color_pixel_format, data)
+    }
+}
+
+struct TofImages {
+    depth: (PsPixelFormat, Vec<u8>),
+    ir: (PsPixelFormat, Vec<u8>),
+    confidence: (PsPixelFormat, Vec<u8>),
+}
+
+/// Bit of `frame_type` in `PsFrameReady`, as decoded by `FrameReady`.
+fn ready_bit(frame_type: PsFrameType) -> PsFrameReady {
+    match frame_type {
+        PsFrameType::DepthFrame => 0x01,
+        PsFrameType::IrFrame => 0x02,
+        PsFrameType::GrayFrame => 0,
+        PsFrameType::RgbFrame => 0x04,
+        PsFrameType::MappedRgbFrame => 0x08,
+        PsFrameType::MappedDepthFrame => 0x10,
+        PsFrameType::MappedIrFrame => 0x20,
+        PsFrameType::ConfidenceFrame => 0x40,
+        PsFrameType::WdrDepthFrame => 0x80,
+    }
+}
+
+/// Farthest depth measured in `depth_range`, in millimeters.
+fn max_depth_mm(depth_range: PsDepthRange) -> f64 {
+    match depth_range {
+        PsDepthRange::Unknown => 0.0,
+        PsDepthRange::NearRange => 1500.0,
+        PsDepthRange::MidRange => 3000.0,
+        PsDepthRange::FarRange => 4400.0,
+        PsDepthRange::XNearRange => 5000.0,
+        PsDepthRange::XMidRange => 6000.0,
+        PsDepthRange::XFarRange => 7500.0,
+        PsDepthRange::XxNearRange => 8000.0,
+        PsDepthRange::XxMidRange => 9000.0,
+        PsDepthRange::XxFarRange => 10000.0,
+    }
+}
+
+/// A distortion-free pinhole camera looking along z.
+struct Camera {
+    origin: Vec3,
+    width: usize,
+    height: usize,
+    focal: f64,
+}
+
+impl Camera {
+    fn depth() -> Self {
+        Camera {
+            origin: [0.0; 3],
+            width: DEPTH_WIDTH,
+            height: DEPTH_HEIGHT,
+            focal: FOCAL_RATIO * DEPTH_WIDTH as f64,
+        }
+    }
+
+    fn rgb(resolution: PsResolution) -> Self {
+        let (width, height) = match resolution {
+            PsResolution::Res1920x1080 => (1920, 1080),
+            PsResolution::Res1280x720 => (1280, 720),
+            PsResolution::Res640x480 => (640, 480),
+            PsResolution::Res640x360 => (640, 360),
+        };
+        Camera {
+            origin: RGB_ORIGIN,
+            width,
+            height,
+            focal: FOCAL_RATIO * width as f64,
+        }
+    }
+
+    fn principal_point(&self) -> (f64, f64) {
+        (self.width as f64 / 2.0, self.height as f64 / 2.0)
+    }
+
+    /// Normalized image coordinates through the center of pixel (`x`, `y`).
+    fn normalized(&self, x: usize, y: usize) -> (f64, f64) {
+        let (cx, cy) = self.principal_point();
+        (
+            (x as f64 + 0.5 - cx) / self.focal,
+            (y as f64 + 0.5 - cy) / self.focal,
+        )
+    }
+
+    /// Point in the space of this camera through the center of pixel (`x`, `y`), at `depth`.
+    fn unproject(&self, x: f64, y: f64, depth: u16) -> PsVector3f {
+        let (cx, cy) = self.principal_point();
+        let z = f64::from(depth);
+        PsVector3f {
+            x: ((x + 0.5 - cx) / self.focal * z) as f32,
+            y: ((y + 0.5 - cy) / self.focal * z) as f32,
+            z: z as f32,
+        }
+    }
+
+    /// Pixel which `point` falls in, and its depth. Points behind the camera give zeros.
+    fn project(&self, point: PsVector3f) -> PsDepthVector3 {
+        let z = f64::from(point.z);
+        if z <= 0.0 {
+            return PsDepthVector3::default();
+        }
+        let (cx, cy) = self.principal_point();
+        PsDepthVector3 {
+            depth_x: (f64::from(point.x) / z * self.focal + cx - 0.5).round() as i32,
+            depth_y: (f64::from(point.y) / z * self.focal + cy - 0.5).round() as i32,
+            depth_z: z.round().min(f64::from(u16::MAX)) as u16,
+        }
+    }
+
+    fn parameters(&self) -> PsCameraParameters {
+        let (cx, cy) = self.principal_point();
+        PsCameraParameters {
+            fx: self.focal,
+            fy: self.focal,
+            cx,
+            cy,
+            k1: 0.0,
+            k2: 0.0,
+            p1: 0.0,
+            p2: 0.0,
+            k3: 0.0,
+            k4: 0.0,
+            k5: 0.0,
+            k6: 0.0,
+        }
+    }
+}
+
+/// xorshift64*, which is plenty for noise.
+#[derive(Debug)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        // the state must not be zero
+        Rng(seed | 1)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform in [0, 1).
+    pub(crate) fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard normal, by Box-Muller.
+    fn gaussian(&mut self) -> f64 {
+        let u1 = 1.0 - self.uniform();
+        let u2 = self.uniform();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
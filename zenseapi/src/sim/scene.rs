@@ -0,0 +1,250 @@
+//! Procedural scenes rendered by ray casting.
+//!
+//! Coordinates are in millimeters in the depth camera frame: x right, y down, z forward.
+
+pub type Vec3 = [f64; 3];
+
+fn add(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: Vec3, s: f64) -> Vec3 {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+fn dot(a: Vec3, b: Vec3) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn normalize(a: Vec3) -> Vec3 {
+    let norm = dot(a, a).sqrt();
+    if norm > 0.0 {
+        scale(a, 1.0 / norm)
+    } else {
+        a
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Shape {
+    /// Points `p` where `dot(normal, p) == distance`.
+    Plane {
+        normal: Vec3,
+        distance: f64,
+    },
+    /// Axis-aligned box between two corners.
+    Box {
+        min: Vec3,
+        max: Vec3,
+    },
+    Sphere {
+        center: Vec3,
+        radius: f64,
+    },
+}
+
+/// Sinusoidal motion of an object around its resting position.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Motion {
+    pub amplitude: Vec3,
+    /// Seconds per oscillation.
+    pub period: f64,
+}
+
+impl Motion {
+    fn offset(&self, time: f64) -> Vec3 {
+        if self.period <= 0.0 {
+            return [0.0; 3];
+        }
+        let phase = (2.0 * std::f64::consts::PI * time / self.period).sin();
+        scale(self.amplitude, phase)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SceneObject {
+    pub shape: Shape,
+    pub color: [u8; 3],
+    /// Infrared reflectivity, from 0 to 1.
+    pub reflectivity: f64,
+    pub motion: Option<Motion>,
+}
+
+impl SceneObject {
+    pub fn new(shape: Shape, color: [u8; 3], reflectivity: f64) -> Self {
+        SceneObject {
+            shape,
+            color,
+            reflectivity,
+            motion: None,
+        }
+    }
+
+    pub fn moving(mut self, motion: Motion) -> Self {
+        self.motion = Some(motion);
+        self
+    }
+
+    /// Distance along `dir` and surface normal of the nearest hit at `time` seconds.
+    fn intersect(&self, origin: Vec3, dir: Vec3, time: f64) -> Option<(f64, Vec3)> {
+        let offset = self.motion.map_or([0.0; 3], |motion| motion.offset(time));
+        let origin = sub(origin, offset);
+        match self.shape {
+            Shape::Plane { normal, distance } => {
+                let normal = normalize(normal);
+                let denominator = dot(normal, dir);
+                if denominator.abs() < 1e-9 {
+                    return None;
+                }
+                let t = (distance - dot(normal, origin)) / denominator;
+                if t > 0.0 {
+                    Some((t, normal))
+                } else {
+                    None
+                }
+            }
+            Shape::Sphere { center, radius } => {
+                let oc = sub(origin, center);
+                let a = dot(dir, dir);
+                let b = dot(oc, dir);
+                let c = dot(oc, oc) - radius * radius;
+                let discriminant = b * b - a * c;
+                if discriminant < 0.0 {
+                    return None;
+                }
+                let t = (-b - discriminant.sqrt()) / a;
+                if t > 0.0 {
+                    let hit = add(origin, scale(dir, t));
+                    Some((t, normalize(sub(hit, center))))
+                } else {
+                    None
+                }
+            }
+            Shape::Box { min, max } => {
+                let mut t_near = f64::NEG_INFINITY;
+                let mut t_far = f64::INFINITY;
+                let mut axis = 0;
+                for i in 0..3 {
+                    if dir[i].abs() < 1e-9 {
+                        if origin[i] < min[i] || origin[i] > max[i] {
+                            return None;
+                        }
+                        continue;
+                    }
+                    let t1 = (min[i] - origin[i]) / dir[i];
+                    let t2 = (max[i] - origin[i]) / dir[i];
+                    let (t1, t2) = if t1 < t2 { (t1, t2) } else { (t2, t1) };
+                    if t1 > t_near {
+                        t_near = t1;
+                        axis = i;
+                    }
+                    t_far = t_far.min(t2);
+                }
+                if t_near > t_far || t_near <= 0.0 {
+                    return None;
+                }
+                let mut normal = [0.0; 3];
+                normal[axis] = -dir[axis].signum();
+                Some((t_near, normal))
+            }
+        }
+    }
+}
+
+/// Result of casting a ray into a `Scene`.
+#[derive(Copy, Clone, Debug)]
+pub struct Hit {
+    /// Depth along the optical axis, in millimeters.
+    pub z: f64,
+    /// Cosine of the angle between the ray and the surface normal.
+    pub cos_incidence: f64,
+    pub color: [u8; 3],
+    pub reflectivity: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scene {
+    pub objects: Vec<SceneObject>,
+}
+
+impl Scene {
+    pub fn empty() -> Self {
+        Scene {
+            objects: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, object: SceneObject) -> Self {
+        self.objects.push(object);
+        self
+    }
+
+    /// Casts a ray from `origin` through the normalized image point (`x`, `y`) at `time` seconds.
+    pub fn cast(&self, origin: Vec3, x: f64, y: f64, time: f64) -> Option<Hit> {
+        let dir = [x, y, 1.0];
+        let mut nearest: Option<(f64, Vec3, &SceneObject)> = None;
+        for object in &self.objects {
+            if let Some((t, normal)) = object.intersect(origin, dir, time) {
+                if nearest.map_or(true, |(nearest_t, _, _)| t < nearest_t) {
+                    nearest = Some((t, normal, object));
+                }
+            }
+        }
+        nearest.map(|(t, normal, object)| Hit {
+            // dir has a unit z component
+            z: t,
+            cos_incidence: (dot(normal, normalize(dir))).abs(),
+            color: object.color,
+            reflectivity: object.reflectivity,
+        })
+    }
+}
+
+/// A room with a back wall, a floor, a box standing on it and a sphere swinging sideways.
+impl Default for Scene {
+    fn default() -> Self {
+        Scene::empty()
+            .with(SceneObject::new(
+                Shape::Plane {
+                    normal: [0.0, 0.0, 1.0],
+                    distance: 3000.0,
+                },
+                [200, 200, 190],
+                0.8,
+            ))
+            .with(SceneObject::new(
+                Shape::Plane {
+                    normal: [0.0, 1.0, 0.0],
+                    distance: 800.0,
+                },
+                [120, 100, 80],
+                0.5,
+            ))
+            .with(SceneObject::new(
+                Shape::Box {
+                    min: [-700.0, 300.0, 1800.0],
+                    max: [-250.0, 800.0, 2200.0],
+                },
+                [40, 90, 200],
+                0.6,
+            ))
+            .with(
+                SceneObject::new(
+                    Shape::Sphere {
+                        center: [250.0, 350.0, 1400.0],
+                        radius: 250.0,
+                    },
+                    [220, 60, 40],
+                    0.7,
+                )
+                .moving(Motion {
+                    amplitude: [400.0, 0.0, 200.0],
+                    period: 4.0,
+                }),
+            )
+    }
+}
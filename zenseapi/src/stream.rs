@@ -0,0 +1,320 @@
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::{
+    Frame, FramePool, FrameReady, FrameRef, FrameSet, FrameType, Session, ZenseError, ZenseResult,
+};
+
+/// A running stream of a session, returned by `Session::start_stream()`.
+///
+/// The stream is stopped when this is dropped, or by `stop()`.
+/// Methods of the session which are allowed while streaming are reachable through `Deref`.
+#[derive(Debug)]
+pub struct Stream<'a> {
+    session: &'a Session<'a>,
+    stopped: bool,
+    stop_requested: Arc<AtomicBool>,
+    sequence: u64,
+    frame_pool: Option<FramePool>,
+}
+
+impl<'a> Stream<'a> {
+    pub(crate) fn new(session: &'a Session<'a>) -> Self {
+        Stream {
+            session,
+            stopped: false,
+            stop_requested: Arc::new(AtomicBool::new(false)),
+            sequence: 0,
+            frame_pool: None,
+        }
+    }
+
+    /// Stops the stream explicitly to observe the result, which `Drop` only logs.
+    pub fn stop(mut self) -> ZenseResult<()> {
+        self.stop_stream()
+    }
+
+    fn stop_stream(&mut self) -> ZenseResult<()> {
+        if self.stopped {
+            return Ok(());
+        }
+        self.stopped = true;
+        match self.session.device.backend().stop_stream(
+            self.session.device.device_handle,
+            self.session.session_index,
+        ) {
+            Ok(()) => Ok(()),
+            Err(n) => Err(self.session.error("Ps2_StopStream", n)),
+        }
+    }
+
+    /// Returns a handle which can request, possibly from another thread, that `frames()` ends.
+    pub fn stop_handle(&self) -> StopHandle {
+        StopHandle {
+            stop_requested: Arc::clone(&self.stop_requested),
+        }
+    }
+
+    /// Returns a blocking iterator which captures a `FrameSet` on every step.
+    ///
+    /// Errors, such as `ReadNextFrameError`, are yielded as items and the iteration goes on,
+    /// except that it ends after an error of class `DeviceLost`.
+    /// The iteration ends once a `StopHandle` of this stream requested to stop, in which case
+    /// the stream is stopped as well.
+    pub fn frames(&mut self) -> Frames<'_, 'a> {
+        Frames {
+            stream: self,
+            frame_types: None,
+            remaining: None,
+            deadline: None,
+            device_lost: false,
+        }
+    }
+
+    pub fn read_next_frame(&mut self) -> ZenseResult<FrameReady> {
+        match self.session.device.backend().read_next_frame(
+            self.session.device.device_handle,
+            self.session.session_index,
+        ) {
+            Ok(frame_ready) => Ok(FrameReady::from(frame_ready)),
+            Err(n) => Err(self.session.error("Ps2_ReadNextFrame", n)),
+        }
+    }
+
+    /// Makes `get_frame()` and `capture()` take frame buffers from `pool`.
+    pub fn set_frame_pool(&mut self, pool: FramePool) {
+        self.frame_pool = Some(pool);
+    }
+
+    pub fn get_frame(&self, frame_type: FrameType) -> ZenseResult<Frame> {
+        let frame = self.get_frame_ref(frame_type)?;
+        Ok(match &self.frame_pool {
+            Some(pool) => frame.to_owned_in(pool),
+            None => frame.to_owned(),
+        })
+    }
+
+    /// Gets a frame without copying it out of the SDK buffer, which the next
+    /// `read_next_frame()` overwrites.
+    ///
+    /// Fails with `InvalidFrameSize` if the length reported by the SDK does not match the
+    /// resolution and pixel format, rather than reading past the buffer.
+    ///
+    /// The frame borrows the stream, so it can neither outlive it nor be kept across a read:
+    ///
+    /// ```compile_fail
+    /// # use zenseapi::enums::FrameType;
+    /// # use zenseapi::sim::SimBackend;
+    /// # use zenseapi::Zense;
+    /// let zense = Zense::with_backend(SimBackend::default()).unwrap();
+    /// let device = zense.open_device("sim://dcam710/0").unwrap();
+    /// let mut session = device.session(0).unwrap();
+    /// let mut stream = session.start_stream().unwrap();
+    /// stream.read_next_frame().unwrap();
+    /// let frame = stream.get_frame_ref(FrameType::DepthFrame).unwrap();
+    /// drop(stream);
+    /// println!("{}", frame.frame_data[0]);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use zenseapi::enums::FrameType;
+    /// # use zenseapi::sim::SimBackend;
+    /// # use zenseapi::Zense;
+    /// let zense = Zense::with_backend(SimBackend::default()).unwrap();
+    /// let device = zense.open_device("sim://dcam710/0").unwrap();
+    /// let mut session = device.session(0).unwrap();
+    /// let mut stream = session.start_stream().unwrap();
+    /// stream.read_next_frame().unwrap();
+    /// let frame = stream.get_frame_ref(FrameType::DepthFrame).unwrap();
+    /// stream.read_next_frame().unwrap();
+    /// println!("{}", frame.frame_data[0]);
+    /// ```
+    pub fn get_frame_ref(&self, frame_type: FrameType) -> ZenseResult<FrameRef<'_>> {
+        match self.session.device.backend().get_frame(
+            self.session.device.device_handle,
+            self.session.session_index,
+            frame_type,
+        ) {
+            Ok(frame) => {
+                let expected_len = usize::from(frame.width)
+                    * usize::from(frame.height)
+                    * frame.pixel_format.bytes_per_pixel();
+                if frame.data_len as usize != expected_len {
+                    return Err(self.error_in("Ps2_GetFrame", ZenseError::InvalidFrameSize));
+                }
+                if frame.frame_data.is_null() && expected_len != 0 {
+                    return Err(self.error_in("Ps2_GetFrame", ZenseError::FramePointerIsNull));
+                }
+                // with a matching length, `Backend` keeps the buffer until the next read or stop
+                // of the session, which need `&mut self` or another stream of the session index
+                Ok(unsafe { FrameRef::from_raw(frame) })
+            }
+            Err(n) => Err(self.session.error("Ps2_GetFrame", n)),
+        }
+    }
+
+    /// Reads the next frames and gets every frame flagged ready, tagged with one sequence number.
+    pub fn capture(&mut self) -> ZenseResult<FrameSet> {
+        let ready = self.read_next_frame()?;
+        let sequence = self.sequence;
+        self.sequence += 1;
+        let get = |is_ready: bool, frame_type: FrameType| -> ZenseResult<Option<Frame>> {
+            if is_ready {
+                self.get_frame(frame_type).map(Some)
+            } else {
+                Ok(None)
+            }
+        };
+        let frame_set = FrameSet {
+            sequence,
+            depth: get(ready.depth, FrameType::DepthFrame)?,
+            ir: get(ready.ir, FrameType::IrFrame)?,
+            rgb: get(ready.rgb, FrameType::RgbFrame)?,
+            mapped_rgb: get(ready.mapped_rgb, FrameType::MappedRgbFrame)?,
+            mapped_depth: get(ready.mapped_depth, FrameType::MappedDepthFrame)?,
+            mapped_ir: get(ready.mapped_ir, FrameType::MappedIrFrame)?,
+            confidence: get(ready.confidence, FrameType::ConfidenceFrame)?,
+            wdr_depth: get(ready.wdr_depth, FrameType::WdrDepthFrame)?,
+        };
+        self.session.device.subscribers.dispatch(&frame_set);
+        Ok(frame_set)
+    }
+}
+
+impl<'a> Deref for Stream<'a> {
+    type Target = Session<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        self.session
+    }
+}
+
+impl Drop for Stream<'_> {
+    fn drop(&mut self) {
+        if let Err(e) = self.stop_stream() {
+            log::error!("Failed to stop stream: {}", e);
+        }
+        self.session.device.streaming[self.session.session_index as usize].set(false);
+    }
+}
+
+/// Requests a `Frames` iterator to end. Obtained by `Stream::stop_handle()`.
+#[derive(Clone, Debug)]
+pub struct StopHandle {
+    stop_requested: Arc<AtomicBool>,
+}
+
+impl StopHandle {
+    pub fn stop(&self) {
+        self.stop_requested.store(true, Ordering::Release);
+    }
+
+    pub fn is_stop_requested(&self) -> bool {
+        self.stop_requested.load(Ordering::Acquire)
+    }
+}
+
+/// Blocking iterator over captures of a running stream, returned by `Stream::frames()`.
+#[derive(Debug)]
+pub struct Frames<'s, 'a> {
+    stream: &'s mut Stream<'a>,
+    frame_types: Option<Vec<FrameType>>,
+    remaining: Option<usize>,
+    deadline: Option<Instant>,
+    device_lost: bool,
+}
+
+impl<'s, 'a> Frames<'s, 'a> {
+    /// Keeps only frames of `frame_types`, skipping captures which contain none of them.
+    pub fn frame_types(mut self, frame_types: &[FrameType]) -> Self {
+        self.frame_types = Some(frame_types.to_vec());
+        self
+    }
+
+    /// Ends after yielding `count` items.
+    pub fn limit(mut self, count: usize) -> Self {
+        self.remaining = Some(count);
+        self
+    }
+
+    /// Ends once `duration` elapsed from now. A capture in progress is not interrupted.
+    pub fn stop_after(mut self, duration: Duration) -> Self {
+        self.deadline = Some(Instant::now() + duration);
+        self
+    }
+
+    /// Yields frames of `frame_type` alone instead of whole sets.
+    pub fn of_type(self, frame_type: FrameType) -> FramesOfType<'s, 'a> {
+        FramesOfType {
+            frames: self.frame_types(&[frame_type]),
+            frame_type,
+        }
+    }
+
+    fn is_finished(&mut self) -> bool {
+        if self.stream.stopped || self.device_lost || self.remaining == Some(0) {
+            return true;
+        }
+        if let Some(deadline) = self.deadline {
+            if Instant::now() >= deadline {
+                return true;
+            }
+        }
+        if self.stream.stop_requested.load(Ordering::Acquire) {
+            if let Err(e) = self.stream.stop_stream() {
+                log::error!("Failed to stop stream: {}", e);
+            }
+            return true;
+        }
+        false
+    }
+}
+
+impl Iterator for Frames<'_, '_> {
+    type Item = ZenseResult<FrameSet>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.is_finished() {
+                return None;
+            }
+            let mut frame_set = match self.stream.capture() {
+                Ok(frame_set) => frame_set,
+                Err(e) => {
+                    self.remaining = self.remaining.map(|n| n - 1);
+                    self.device_lost = e.is_device_lost();
+                    return Some(Err(e));
+                }
+            };
+            if let Some(frame_types) = &self.frame_types {
+                frame_set.retain(frame_types);
+                if frame_set.is_empty() {
+                    continue;
+                }
+            }
+            self.remaining = self.remaining.map(|n| n - 1);
+            return Some(Ok(frame_set));
+        }
+    }
+}
+
+/// Blocking iterator over frames of one type, returned by `Frames::of_type()`.
+#[derive(Debug)]
+pub struct FramesOfType<'s, 'a> {
+    frames: Frames<'s, 'a>,
+    frame_type: FrameType,
+}
+
+impl Iterator for FramesOfType<'_, '_> {
+    type Item = ZenseResult<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frame_type = self.frame_type;
+        self.frames.find_map(|frame_set| match frame_set {
+            Ok(mut frame_set) => frame_set.take(frame_type).map(Ok),
+            Err(e) => Some(Err(e)),
+        })
+    }
+}
@@ -1,30 +1,86 @@
+use std::cell::{Cell, RefCell};
 use std::convert::TryInto;
 use std::ffi::CString;
+use std::os::raw::c_int;
+use std::sync::Arc;
 
-use raw::types::{PsCameraParameters, PsDeviceHandle, PsWdrOutputMode};
+use raw::types::{
+    PsCameraExtrinsicParameters, PsCameraParameters, PsDepthVector3, PsDeviceHandle,
+    PsWdrOutputMode,
+};
 use zenseapi_sys as raw;
 
-use crate::enums::{
-    GmmGainEffectiveTime, PropertyType, PropertyValue, Resolution, SensorType, WdrStyle,
-};
+use crate::enums::Resolution;
+use crate::subscription::Subscribers;
 use crate::{
-    ConnectStatus, DataMode, DepthRange, DeviceType, FrameType, PixelFormat, ZenseError,
-    ZenseResult,
+    Backend, ConnectStatus, DepthRange, DeviceType, Error, FrameBuffer, FramePool, FrameType,
+    PixelFormat, Session, Subscription, Zense, ZenseError, ZenseResult,
 };
 
 pub type CameraParameters = PsCameraParameters;
 pub type WdrOutputMode = PsWdrOutputMode;
+pub type DepthVector3 = PsDepthVector3;
+
+/// Pose of the RGB sensor relative to the depth sensor, mapping a point in the depth sensor
+/// space to `rotation * point + translation` in the RGB sensor space.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CameraExtrinsics {
+    /// Rows of the rotation matrix.
+    pub rotation: [[f64; 3]; 3],
+    /// Translation in millimeters.
+    pub translation: [f64; 3],
+}
 
-macro_rules! handle_check {
-    ($self_:ident, $b:expr) => {
-        if $self_.device_handle.is_null() {
-            Err(ZenseError::FfiError)
-        } else if $self_.device_closed {
-            Err(ZenseError::CameraNotOpened)
-        } else {
-            $b
+impl CameraExtrinsics {
+    pub(crate) fn from(parameters: PsCameraExtrinsicParameters) -> Self {
+        let r = parameters.rotation;
+        CameraExtrinsics {
+            rotation: [[r[0], r[1], r[2]], [r[3], r[4], r[5]], [r[6], r[7], r[8]]],
+            translation: parameters.translation,
         }
-    };
+    }
+
+    /// The transform from the RGB sensor space back to the depth sensor space.
+    pub fn inverse(&self) -> Self {
+        let r = self.rotation;
+        let t = self.translation;
+        // the inverse of a rotation is its transpose
+        let rotation = [
+            [r[0][0], r[1][0], r[2][0]],
+            [r[0][1], r[1][1], r[2][1]],
+            [r[0][2], r[1][2], r[2][2]],
+        ];
+        let translation = [
+            -(rotation[0][0] * t[0] + rotation[0][1] * t[1] + rotation[0][2] * t[2]),
+            -(rotation[1][0] * t[0] + rotation[1][1] * t[1] + rotation[1][2] * t[2]),
+            -(rotation[2][0] * t[0] + rotation[2][1] * t[1] + rotation[2][2] * t[2]),
+        ];
+        CameraExtrinsics {
+            rotation,
+            translation,
+        }
+    }
+
+    /// Transforms `point`, in millimeters, from the depth sensor space to the RGB sensor space.
+    pub fn depth_to_rgb(&self, point: [f32; 3]) -> [f32; 3] {
+        let r = self.rotation;
+        let t = self.translation;
+        let [x, y, z] = [
+            f64::from(point[0]),
+            f64::from(point[1]),
+            f64::from(point[2]),
+        ];
+        [
+            (r[0][0] * x + r[0][1] * y + r[0][2] * z + t[0]) as f32,
+            (r[1][0] * x + r[1][1] * y + r[1][2] * z + t[1]) as f32,
+            (r[2][0] * x + r[2][1] * y + r[2][2] * z + t[2]) as f32,
+        ]
+    }
+
+    /// Transforms `point`, in millimeters, from the RGB sensor space to the depth sensor space.
+    pub fn rgb_to_depth(&self, point: [f32; 3]) -> [f32; 3] {
+        self.inverse().depth_to_rgb(point)
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -36,376 +92,134 @@ pub struct DeviceInfo {
     pub status: ConnectStatus,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub struct DeviceHandle {
-    device_handle: PsDeviceHandle,
+/// An opened device. The device is closed when this is dropped, or by `close_device()`.
+///
+/// A device opened by `Zense::open_device_shared()` is a `DeviceHandle<'static>`, which can be
+/// moved to another thread, such as into a `CaptureWorker`.
+#[derive(Debug)]
+pub struct DeviceHandle<'a> {
+    pub(crate) device_handle: PsDeviceHandle,
+    uri: String,
     device_closed: bool,
+    session_count: u32,
+    pub(crate) subscribers: Arc<Subscribers>,
+    // indexed by session
+    pub(crate) last_set: RefCell<Vec<LastSet>>,
+    // indexed by session, set while a `Stream` of the session exists
+    pub(crate) streaming: Vec<Cell<bool>>,
+    context: ContextRef<'a>,
 }
 
-impl DeviceHandle {
-    pub fn new(device_handle: PsDeviceHandle) -> Self {
-        DeviceHandle {
-            device_handle,
-            device_closed: false,
-        }
-    }
-
-    pub fn close_device(&mut self) -> ZenseResult<()> {
-        handle_check!(
-            self,
-            match raw::close_device(self.device_handle) {
-                Ok(()) => {
-                    self.device_closed = true;
-                    Ok(())
-                }
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn start_stream(&self, session_index: u32) -> ZenseResult<()> {
-        handle_check!(
-            self,
-            match raw::start_stream(self.device_handle, session_index) {
-                Ok(()) => Ok(()),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn stop_stream(&self, session_index: u32) -> ZenseResult<()> {
-        handle_check!(
-            self,
-            match raw::stop_stream(self.device_handle, session_index) {
-                Ok(()) => Ok(()),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn read_next_frame(&self, session_index: u32) -> ZenseResult<FrameReady> {
-        handle_check!(
-            self,
-            match raw::read_next_frame(self.device_handle, session_index) {
-                Ok(frame_ready) => Ok(FrameReady::from(frame_ready)),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn get_frame(&self, session_index: u32, frame_type: FrameType) -> ZenseResult<Frame> {
-        handle_check!(
-            self,
-            match raw::get_frame(self.device_handle, session_index, frame_type) {
-                Ok(frame) => Ok(Frame::from(frame)),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn set_data_mode(&self, session_index: u32, data_mode: DataMode) -> ZenseResult<()> {
-        handle_check!(
-            self,
-            match raw::set_data_mode(self.device_handle, session_index, data_mode) {
-                Ok(()) => Ok(()),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn get_data_mode(&self, session_index: u32) -> ZenseResult<DataMode> {
-        handle_check!(
-            self,
-            match raw::get_data_mode(self.device_handle, session_index) {
-                Ok(data_mode) => Ok(data_mode),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn get_depth_range(&self, session_index: u32) -> ZenseResult<DepthRange> {
-        handle_check!(
-            self,
-            match raw::get_depth_range(self.device_handle, session_index) {
-                Ok(depth_range) => Ok(depth_range),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn set_depth_range(&self, session_index: u32, depth_range: DepthRange) -> ZenseResult<()> {
-        handle_check!(
-            self,
-            match raw::set_depth_range(self.device_handle, session_index, depth_range) {
-                Ok(()) => Ok(()),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn get_threshold(&self, session_index: u32) -> ZenseResult<u16> {
-        handle_check!(
-            self,
-            match raw::get_threshold(self.device_handle, session_index) {
-                Ok(threshold) => Ok(threshold),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn set_threshold(&self, session_index: u32, threshold: u16) -> ZenseResult<()> {
-        handle_check!(
-            self,
-            match raw::set_threshold(self.device_handle, session_index, threshold) {
-                Ok(()) => Ok(()),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn get_pulse_count(&self, session_index: u32) -> ZenseResult<u16> {
-        handle_check!(
-            self,
-            match raw::get_pulse_count(self.device_handle, session_index) {
-                Ok(pulse_count) => Ok(pulse_count),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn set_pulse_count(&self, session_index: u32, pulse_count: u16) -> ZenseResult<()> {
-        handle_check!(
-            self,
-            match raw::set_pulse_count(self.device_handle, session_index, pulse_count) {
-                Ok(()) => Ok(()),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn get_gmm_gain(&self, session_index: u32) -> ZenseResult<u16> {
-        handle_check!(
-            self,
-            match raw::get_gmm_gain(self.device_handle, session_index) {
-                Ok(gmm_gain) => Ok(gmm_gain),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn set_gmm_gain(
-        &self,
-        session_index: u32,
-        gmm_gain: u16,
-        option: GmmGainEffectiveTime,
-    ) -> ZenseResult<()> {
-        handle_check!(
-            self,
-            match raw::set_gmm_gain(self.device_handle, session_index, gmm_gain, option) {
-                Ok(()) => Ok(()),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn get_property(
-        &self,
-        session_index: u32,
-        property_type: PropertyType,
-    ) -> ZenseResult<PropertyValue> {
-        handle_check!(
-            self,
-            match raw::get_property(self.device_handle, session_index, property_type) {
-                Ok(PropertyValue::StringValue(cstr)) => Ok(PropertyValue::StringValue(cstr)),
-                Ok(_) => todo!("missing DataMode, DataModeList, DepthRangeList implementation"),
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
-
-    pub fn set_property(
-        &self,
-        _session_index: u32,
-        _property_type: PropertyType,
-        _data: PropertyValue,
-    ) -> ZenseResult<()> {
-        unimplemented!();
-        // handle_check!(
-        //     self,
-        //     match raw::set_property(self.device_handle, session_index, property_type, data) {
-        //         Ok(()) => Ok(()),
-        //         Err(n) => Err(ZenseError::from_int(n)),
-        //     }
-        // )
-    }
-
-    pub fn get_camera_parameters(
-        &self,
-        session_index: u32,
-        sensor_type: SensorType,
-    ) -> ZenseResult<CameraParameters> {
-        handle_check!(
-            self,
-            match raw::get_camera_parameters(self.device_handle, session_index, sensor_type) {
-                Ok(camera_parameters) => {
-                    let error = 0.001f64;
-                    if (camera_parameters.cx - f64::default()).abs() < error
-                        && (camera_parameters.cy - f64::default()).abs() < error
-                        && (camera_parameters.fx - f64::default()).abs() < error
-                        && (camera_parameters.fy - f64::default()).abs() < error
-                        && (camera_parameters.k1 - f64::default()).abs() < error
-                        && (camera_parameters.k2 - f64::default()).abs() < error
-                        && (camera_parameters.k3 - f64::default()).abs() < error
-                        && (camera_parameters.k4 - f64::default()).abs() < error
-                        && (camera_parameters.k5 - f64::default()).abs() < error
-                        && (camera_parameters.k6 - f64::default()).abs() < error
-                        && (camera_parameters.p1 - f64::default()).abs() < error
-                        && (camera_parameters.p2 - f64::default()).abs() < error
-                    {
-                        Err(ZenseError::FfiError)
-                    } else {
-                        Ok(camera_parameters)
-                    }
-                }
-                Err(n) => Err(ZenseError::from_int(n)),
-            }
-        )
-    }
+/// The context a `DeviceHandle` was opened from.
+#[derive(Debug)]
+pub(crate) enum ContextRef<'a> {
+    Borrowed(&'a Zense),
+    /// Kept alive by the device, see `Zense::open_device_shared()`.
+    Shared(Arc<Zense>),
+}
 
-    pub fn set_wdr_output_mode(
-        &self,
-        session_index: u32,
-        wdr_mode: WdrOutputMode,
-    ) -> ZenseResult<()> {
-        match raw::set_wdr_output_mode(self.device_handle, session_index, wdr_mode) {
-            Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
+impl ContextRef<'_> {
+    pub(crate) fn zense(&self) -> &Zense {
+        match self {
+            ContextRef::Borrowed(zense) => zense,
+            ContextRef::Shared(zense) => zense,
         }
     }
+}
 
-    pub fn set_wdr_style(&self, session_index: u32, wdr_style: WdrStyle) -> ZenseResult<()> {
-        match raw::set_wdr_style(self.device_handle, session_index, wdr_style) {
-            Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
-        }
-    }
+/// Settings which the SDK cannot report, as last set through a session.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct LastSet {
+    pub(crate) rgb_resolution: Option<Resolution>,
+    pub(crate) color_pixel_format: Option<PixelFormat>,
+}
 
-    pub fn set_rgb_frame_enabled(&self, session_index: u32, enabled: bool) -> ZenseResult<()> {
-        match raw::set_rgb_frame_enabled(self.device_handle, session_index, enabled) {
-            Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
+impl<'a> DeviceHandle<'a> {
+    pub(crate) fn new(
+        context: ContextRef<'a>,
+        device_handle: PsDeviceHandle,
+        uri: &str,
+        session_count: u32,
+    ) -> Self {
+        DeviceHandle {
+            device_handle,
+            uri: uri.to_string(),
+            device_closed: false,
+            session_count,
+            subscribers: Arc::default(),
+            last_set: RefCell::new(vec![LastSet::default(); session_count as usize]),
+            streaming: vec![Cell::new(false); session_count as usize],
+            context,
         }
     }
 
-    pub fn set_depth_distortion_correction_enabled(
-        &self,
-        session_index: u32,
-        enabled: bool,
-    ) -> ZenseResult<()> {
-        match raw::set_depth_distortion_correction_enabled(
-            self.device_handle,
-            session_index,
-            enabled,
-        ) {
-            Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
-        }
+    pub(crate) fn backend(&self) -> &dyn Backend {
+        self.context.zense().backend()
     }
 
-    pub fn set_ir_distortion_correction_enabled(
-        &self,
-        session_index: u32,
-        enabled: bool,
-    ) -> ZenseResult<()> {
-        match raw::set_ir_distortion_correction_enabled(self.device_handle, session_index, enabled)
-        {
-            Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
-        }
+    /// Error of `function` of the SDK, which returned `status` for this device.
+    pub(crate) fn error(&self, function: &'static str, status: c_int) -> Error {
+        Error::from_status(function, status).on_device(&self.uri)
     }
 
-    pub fn set_rgb_distortion_correction_enabled(
-        &self,
-        session_index: u32,
-        enabled: bool,
-    ) -> ZenseResult<()> {
-        match raw::set_rgb_distortion_correction_enabled(self.device_handle, session_index, enabled)
-        {
-            Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
-        }
+    /// URI the device was opened with.
+    pub fn uri(&self) -> &str {
+        &self.uri
     }
 
-    pub fn set_compute_real_depth_correction_enabled(
-        &self,
-        session_index: u32,
-        enabled: bool,
-    ) -> ZenseResult<()> {
-        match raw::set_compute_real_depth_correction_enabled(
-            self.device_handle,
-            session_index,
-            enabled,
-        ) {
-            Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
-        }
+    pub fn session_count(&self) -> u32 {
+        self.session_count
     }
 
-    pub fn set_spatial_filter_enabled(&self, session_index: u32, enabled: bool) -> ZenseResult<()> {
-        match raw::set_spatial_filter_enabled(self.device_handle, session_index, enabled) {
-            Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
+    /// Returns the session at `session_index`, which must be less than `session_count()`.
+    ///
+    /// Several `Session`s of one index may exist, but only one of them can stream at a time.
+    pub fn session(&self, session_index: u32) -> ZenseResult<Session<'_>> {
+        if session_index < self.session_count {
+            Ok(Session::new(self, session_index))
+        } else {
+            Err(Error::from(ZenseError::InvalidSessionIndex)
+                .on_device(&self.uri)
+                .in_session(session_index))
         }
     }
 
-    pub fn set_time_filter_enabled(&self, session_index: u32, enabled: bool) -> ZenseResult<()> {
-        match raw::set_time_filter_enabled(self.device_handle, session_index, enabled) {
-            Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
-        }
+    /// Registers `callback` to be called with every frame of `frame_type` captured from this
+    /// device by `Stream::capture()`, and so by `Stream::frames()` and `CaptureWorker` as well.
+    ///
+    /// Subscriptions can be added and removed while streaming. A panicking callback is logged
+    /// and unsubscribed without affecting the capture.
+    pub fn subscribe<F>(&self, frame_type: FrameType, callback: F) -> Subscription
+    where
+        F: FnMut(&Frame) + Send + 'static,
+    {
+        self.subscribers.subscribe(frame_type, callback)
     }
 
-    pub fn set_mapper_enabled_rgb_to_depth(
-        &self,
-        session_index: u32,
-        enabled: bool,
-    ) -> ZenseResult<()> {
-        match raw::set_mapper_enabled_rgb_to_depth(self.device_handle, session_index, enabled) {
+    /// Closes the device explicitly to observe the result, which `Drop` only logs.
+    pub fn close_device(mut self) -> ZenseResult<()> {
+        self.device_closed = true;
+        match self.backend().close_device(self.device_handle) {
             Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
+            Err(n) => Err(self.error("Ps2_CloseDevice", n)),
         }
     }
+}
 
-    pub fn set_mapper_enabled_depth_to_rgb(
-        &self,
-        session_index: u32,
-        enabled: bool,
-    ) -> ZenseResult<()> {
-        match raw::set_mapper_enabled_depth_to_rgb(self.device_handle, session_index, enabled) {
-            Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
-        }
-    }
+// The SDK handle may be used from any thread as long as it is not used concurrently,
+// which holds as `DeviceHandle` is not `Sync`.
+unsafe impl Send for DeviceHandle<'_> {}
 
-    pub fn set_rgb_resolution(
-        &self,
-        session_index: u32,
-        resolution: Resolution,
-    ) -> ZenseResult<()> {
-        match raw::set_rgb_resolution(self.device_handle, session_index, resolution) {
-            Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
+impl Drop for DeviceHandle<'_> {
+    fn drop(&mut self) {
+        if self.device_closed {
+            return;
         }
-    }
-
-    pub fn set_color_pixel_format(
-        &self,
-        session_index: u32,
-        pixel_format: PixelFormat,
-    ) -> ZenseResult<()> {
-        match raw::set_color_pixel_format(self.device_handle, session_index, pixel_format) {
-            Ok(()) => Ok(()),
-            Err(n) => Err(ZenseError::from_int(n)),
+        if let Err(n) = self.backend().close_device(self.device_handle) {
+            log::error!(
+                "Failed to close device: {}",
+                self.error("Ps2_CloseDevice", n)
+            );
         }
     }
 }
@@ -445,7 +259,7 @@ pub struct Frame {
     pub frame_type: FrameType,
     pub pixel_format: PixelFormat,
     pub imu_frame_no: u8,
-    pub frame_data: Vec<u8>,
+    pub frame_data: FrameBuffer,
     pub exposure_time: f32,
     pub depth_range: DepthRange,
     pub width: u16,
@@ -453,11 +267,50 @@ pub struct Frame {
 }
 
 impl Frame {
-    pub(crate) fn from(ps_frame: raw::types::PsFrame) -> Self {
+    pub fn as_frame_ref(&self) -> FrameRef<'_> {
+        FrameRef {
+            frame_index: self.frame_index,
+            frame_type: self.frame_type,
+            pixel_format: self.pixel_format,
+            imu_frame_no: self.imu_frame_no,
+            frame_data: &self.frame_data,
+            exposure_time: self.exposure_time,
+            depth_range: self.depth_range,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+/// A frame borrowing the buffer of the SDK, returned by `Stream::get_frame_ref()`.
+///
+/// The buffer is only valid until the next `read_next_frame()` of the stream, which the
+/// borrow enforces. Use `to_owned()` to keep the frame longer.
+#[derive(Copy, Clone, Debug)]
+pub struct FrameRef<'a> {
+    pub frame_index: u32,
+    pub frame_type: FrameType,
+    pub pixel_format: PixelFormat,
+    pub imu_frame_no: u8,
+    pub frame_data: &'a [u8],
+    pub exposure_time: f32,
+    pub depth_range: DepthRange,
+    pub width: u16,
+    pub height: u16,
+}
+
+impl<'a> FrameRef<'a> {
+    /// # Safety
+    ///
+    /// `ps_frame.frame_data` must point to `ps_frame.data_len` bytes which stay valid for `'a`.
+    pub(crate) unsafe fn from_raw(ps_frame: raw::types::PsFrame) -> Self {
         let data_length: usize = ps_frame.data_len.try_into().unwrap();
-        let frame_data: Vec<u8> =
-            unsafe { std::slice::from_raw_parts(ps_frame.frame_data, data_length) }.to_vec();
-        Frame {
+        let frame_data: &'a [u8] = if ps_frame.frame_data.is_null() || data_length == 0 {
+            &[]
+        } else {
+            std::slice::from_raw_parts(ps_frame.frame_data, data_length)
+        };
+        FrameRef {
             frame_index: ps_frame.frame_index,
             frame_type: ps_frame.frame_type,
             pixel_format: ps_frame.pixel_format,
@@ -469,4 +322,123 @@ impl Frame {
             height: ps_frame.height,
         }
     }
+
+    /// Copies the frame out of the SDK buffer.
+    pub fn to_owned(&self) -> Frame {
+        self.with_frame_data(FrameBuffer::from(self.frame_data.to_vec()))
+    }
+
+    /// Copies the frame out of the SDK buffer into a buffer of `pool`.
+    pub fn to_owned_in(&self, pool: &FramePool) -> Frame {
+        let mut frame_data = pool.acquire(
+            self.frame_type,
+            self.width,
+            self.height,
+            self.frame_data.len(),
+        );
+        frame_data.copy_from_slice(self.frame_data);
+        self.with_frame_data(frame_data)
+    }
+
+    fn with_frame_data(&self, frame_data: FrameBuffer) -> Frame {
+        Frame {
+            frame_index: self.frame_index,
+            frame_type: self.frame_type,
+            pixel_format: self.pixel_format,
+            imu_frame_no: self.imu_frame_no,
+            frame_data,
+            exposure_time: self.exposure_time,
+            depth_range: self.depth_range,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+/// All frames which were ready after one `read_next_frame()`, as returned by `Stream::capture()`.
+#[derive(Clone, Debug, Default)]
+pub struct FrameSet {
+    /// Number of the capture in its stream, starting from 0.
+    pub sequence: u64,
+    pub depth: Option<Frame>,
+    pub ir: Option<Frame>,
+    pub rgb: Option<Frame>,
+    pub mapped_rgb: Option<Frame>,
+    pub mapped_depth: Option<Frame>,
+    pub mapped_ir: Option<Frame>,
+    pub confidence: Option<Frame>,
+    pub wdr_depth: Option<Frame>,
+}
+
+impl FrameSet {
+    pub fn get(&self, frame_type: FrameType) -> Option<&Frame> {
+        match frame_type {
+            FrameType::DepthFrame => self.depth.as_ref(),
+            FrameType::IrFrame => self.ir.as_ref(),
+            FrameType::GrayFrame => None,
+            FrameType::RgbFrame => self.rgb.as_ref(),
+            FrameType::MappedRgbFrame => self.mapped_rgb.as_ref(),
+            FrameType::MappedDepthFrame => self.mapped_depth.as_ref(),
+            FrameType::MappedIrFrame => self.mapped_ir.as_ref(),
+            FrameType::ConfidenceFrame => self.confidence.as_ref(),
+            FrameType::WdrDepthFrame => self.wdr_depth.as_ref(),
+        }
+    }
+
+    pub fn take(&mut self, frame_type: FrameType) -> Option<Frame> {
+        match frame_type {
+            FrameType::DepthFrame => self.depth.take(),
+            FrameType::IrFrame => self.ir.take(),
+            FrameType::GrayFrame => None,
+            FrameType::RgbFrame => self.rgb.take(),
+            FrameType::MappedRgbFrame => self.mapped_rgb.take(),
+            FrameType::MappedDepthFrame => self.mapped_depth.take(),
+            FrameType::MappedIrFrame => self.mapped_ir.take(),
+            FrameType::ConfidenceFrame => self.confidence.take(),
+            FrameType::WdrDepthFrame => self.wdr_depth.take(),
+        }
+    }
+
+    /// Drops every frame whose type is not in `frame_types`.
+    pub fn retain(&mut self, frame_types: &[FrameType]) {
+        let mut retained = FrameSet {
+            sequence: self.sequence,
+            ..FrameSet::default()
+        };
+        for &frame_type in frame_types {
+            let frame = self.take(frame_type);
+            match frame_type {
+                FrameType::DepthFrame => retained.depth = frame,
+                FrameType::IrFrame => retained.ir = frame,
+                FrameType::GrayFrame => {}
+                FrameType::RgbFrame => retained.rgb = frame,
+                FrameType::MappedRgbFrame => retained.mapped_rgb = frame,
+                FrameType::MappedDepthFrame => retained.mapped_depth = frame,
+                FrameType::MappedIrFrame => retained.mapped_ir = frame,
+                FrameType::ConfidenceFrame => retained.confidence = frame,
+                FrameType::WdrDepthFrame => retained.wdr_depth = frame,
+            }
+        }
+        *self = retained;
+    }
+
+    /// Iterates over the frames present in this set.
+    pub fn iter(&self) -> impl Iterator<Item = &Frame> {
+        vec![
+            &self.depth,
+            &self.ir,
+            &self.rgb,
+            &self.mapped_rgb,
+            &self.mapped_depth,
+            &self.mapped_ir,
+            &self.confidence,
+            &self.wdr_depth,
+        ]
+        .into_iter()
+        .filter_map(Option::as_ref)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
 }
@@ -0,0 +1,114 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+
+use crate::{Frame, FrameSet, FrameType};
+
+type Callback = Arc<Mutex<dyn FnMut(&Frame) + Send>>;
+
+struct Entry {
+    id: u64,
+    frame_type: FrameType,
+    callback: Callback,
+}
+
+/// Callbacks registered by `DeviceHandle::subscribe()`, called by `Stream::capture()`.
+#[derive(Default)]
+pub(crate) struct Subscribers {
+    entries: Mutex<Vec<Entry>>,
+    next_id: AtomicU64,
+}
+
+impl Subscribers {
+    fn lock(&self) -> MutexGuard<'_, Vec<Entry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn subscribe<F>(self: &Arc<Self>, frame_type: FrameType, callback: F) -> Subscription
+    where
+        F: FnMut(&Frame) + Send + 'static,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.lock().push(Entry {
+            id,
+            frame_type,
+            callback: Arc::new(Mutex::new(callback)),
+        });
+        Subscription {
+            subscribers: Arc::downgrade(self),
+            id,
+        }
+    }
+
+    fn unsubscribe(&self, id: u64) {
+        self.lock().retain(|entry| entry.id != id);
+    }
+
+    /// Calls the callbacks subscribing to each frame of `frame_set`.
+    ///
+    /// Callbacks run without the registry locked, so they may subscribe and unsubscribe.
+    /// A callback which panics is unsubscribed, and the others keep being called.
+    pub(crate) fn dispatch(&self, frame_set: &FrameSet) {
+        let targets: Vec<(u64, FrameType, Callback)> = {
+            let entries = self.lock();
+            if entries.is_empty() {
+                return;
+            }
+            entries
+                .iter()
+                .map(|entry| (entry.id, entry.frame_type, Arc::clone(&entry.callback)))
+                .collect()
+        };
+        for (id, frame_type, callback) in targets {
+            let frame = match frame_set.get(frame_type) {
+                Some(frame) => frame,
+                None => continue,
+            };
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                let mut callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+                (*callback)(frame)
+            }));
+            if result.is_err() {
+                log::error!("Callback for {:?} panicked and is unsubscribed", frame_type);
+                self.unsubscribe(id);
+            }
+        }
+    }
+}
+
+/// A callback registered by `DeviceHandle::subscribe()`. Dropping it unsubscribes the callback.
+///
+/// A capture being dispatched on another thread may still call the callback once after that.
+#[must_use = "the callback is unsubscribed when the Subscription is dropped"]
+pub struct Subscription {
+    subscribers: Weak<Subscribers>,
+    id: u64,
+}
+
+impl Subscription {
+    pub fn unsubscribe(self) {}
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(subscribers) = self.subscribers.upgrade() {
+            subscribers.unsubscribe(self.id);
+        }
+    }
+}
+
+impl std::fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscribers")
+            .field("len", &self.lock().len())
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for Subscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription")
+            .field("id", &self.id)
+            .finish()
+    }
+}
@@ -0,0 +1,239 @@
+use std::convert::TryFrom;
+use std::slice::ChunksExact;
+
+use crate::{Error, Frame, FrameType, PixelFormat, ZenseError, ZenseResult};
+
+/// Checks `frame` against the accepted frame types and pixel formats, and its size.
+fn validate(
+    frame: &Frame,
+    frame_types: &[FrameType],
+    pixel_formats: &[PixelFormat],
+) -> ZenseResult<()> {
+    if !frame_types.contains(&frame.frame_type) || !pixel_formats.contains(&frame.pixel_format) {
+        return Err(ZenseError::InvalidFrameFormat.into());
+    }
+    let expected =
+        frame.width as usize * frame.height as usize * frame.pixel_format.bytes_per_pixel();
+    if frame.frame_data.len() != expected {
+        return Err(ZenseError::InvalidFrameSize.into());
+    }
+    Ok(())
+}
+
+/// Views 16-bit pixels in native byte order, which is little-endian on every SDK platform.
+fn as_u16_slice(data: &[u8]) -> ZenseResult<&[u16]> {
+    // buffers from the global allocator are aligned well beyond 2 bytes
+    match unsafe { data.align_to::<u16>() } {
+        (&[], pixels, &[]) => Ok(pixels),
+        _ => Err(ZenseError::InvalidFrameFormat.into()),
+    }
+}
+
+/// Depth frame in millimeters: `DepthFrame`, `MappedDepthFrame` or `WdrDepthFrame` in
+/// `DepthMm16`.
+#[derive(Clone, Debug)]
+pub struct DepthFrame {
+    frame: Frame,
+}
+
+impl DepthFrame {
+    pub fn width(&self) -> usize {
+        self.frame.width as usize
+    }
+
+    pub fn height(&self) -> usize {
+        self.frame.height as usize
+    }
+
+    /// Depth at (`x`, `y`) in millimeters, 0 where it is invalid.
+    pub fn depth_mm(&self, x: usize, y: usize) -> Option<u16> {
+        if x < self.width() && y < self.height() {
+            Some(self.as_slice()[y * self.width() + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn as_slice(&self) -> &[u16] {
+        as_u16_slice(&self.frame.frame_data).unwrap()
+    }
+
+    pub fn rows(&self) -> ChunksExact<'_, u16> {
+        self.as_slice().chunks_exact(self.width().max(1))
+    }
+
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+
+    pub fn into_frame(self) -> Frame {
+        self.frame
+    }
+}
+
+impl TryFrom<Frame> for DepthFrame {
+    type Error = Error;
+
+    fn try_from(frame: Frame) -> ZenseResult<Self> {
+        validate(
+            &frame,
+            &[
+                FrameType::DepthFrame,
+                FrameType::MappedDepthFrame,
+                FrameType::WdrDepthFrame,
+            ],
+            &[PixelFormat::DepthMm16],
+        )?;
+        as_u16_slice(&frame.frame_data)?;
+        Ok(DepthFrame { frame })
+    }
+}
+
+/// Pixels of an `IrFrame`, or of one of its rows.
+#[derive(Copy, Clone, Debug)]
+pub enum IrPixels<'a> {
+    Gray16(&'a [u16]),
+    Gray8(&'a [u8]),
+}
+
+/// Infrared or confidence frame: `IrFrame`, `MappedIrFrame`, `GrayFrame` or `ConfidenceFrame`
+/// in `Gray16` or `Gray8`.
+#[derive(Clone, Debug)]
+pub struct IrFrame {
+    frame: Frame,
+}
+
+impl IrFrame {
+    pub fn width(&self) -> usize {
+        self.frame.width as usize
+    }
+
+    pub fn height(&self) -> usize {
+        self.frame.height as usize
+    }
+
+    /// Intensity at (`x`, `y`). `Gray8` values are widened as they are.
+    pub fn ir(&self, x: usize, y: usize) -> Option<u16> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        let i = y * self.width() + x;
+        Some(match self.pixels() {
+            IrPixels::Gray16(pixels) => pixels[i],
+            IrPixels::Gray8(pixels) => u16::from(pixels[i]),
+        })
+    }
+
+    pub fn pixels(&self) -> IrPixels<'_> {
+        match self.frame.pixel_format {
+            PixelFormat::Gray8 => IrPixels::Gray8(&self.frame.frame_data),
+            _ => IrPixels::Gray16(as_u16_slice(&self.frame.frame_data).unwrap()),
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = IrPixels<'_>> {
+        let width = self.width().max(1);
+        let (gray16, gray8) = match self.pixels() {
+            IrPixels::Gray16(pixels) => (Some(pixels.chunks_exact(width)), None),
+            IrPixels::Gray8(pixels) => (None, Some(pixels.chunks_exact(width))),
+        };
+        gray16
+            .into_iter()
+            .flatten()
+            .map(IrPixels::Gray16)
+            .chain(gray8.into_iter().flatten().map(IrPixels::Gray8))
+    }
+
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+
+    pub fn into_frame(self) -> Frame {
+        self.frame
+    }
+}
+
+impl TryFrom<Frame> for IrFrame {
+    type Error = Error;
+
+    fn try_from(frame: Frame) -> ZenseResult<Self> {
+        validate(
+            &frame,
+            &[
+                FrameType::IrFrame,
+                FrameType::MappedIrFrame,
+                FrameType::GrayFrame,
+                FrameType::ConfidenceFrame,
+            ],
+            &[PixelFormat::Gray16, PixelFormat::Gray8],
+        )?;
+        if frame.pixel_format == PixelFormat::Gray16 {
+            as_u16_slice(&frame.frame_data)?;
+        }
+        Ok(IrFrame { frame })
+    }
+}
+
+/// Color frame: `RgbFrame` or `MappedRgbFrame` in `Rgb888` or `Bgr888`.
+#[derive(Clone, Debug)]
+pub struct ColorFrame {
+    frame: Frame,
+}
+
+impl ColorFrame {
+    pub fn width(&self) -> usize {
+        self.frame.width as usize
+    }
+
+    pub fn height(&self) -> usize {
+        self.frame.height as usize
+    }
+
+    /// Color at (`x`, `y`) as `[r, g, b]`, whichever the pixel format is.
+    pub fn rgb(&self, x: usize, y: usize) -> Option<[u8; 3]> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        let [a, b, c] = self.as_slice()[y * self.width() + x];
+        Some(match self.frame.pixel_format {
+            PixelFormat::Bgr888 => [c, b, a],
+            _ => [a, b, c],
+        })
+    }
+
+    /// Pixels in the order of `pixel_format()`.
+    pub fn as_slice(&self) -> &[[u8; 3]] {
+        let data = &self.frame.frame_data;
+        // [u8; 3] has the alignment of u8, and the length was validated
+        unsafe { std::slice::from_raw_parts(data.as_ptr() as *const [u8; 3], data.len() / 3) }
+    }
+
+    pub fn rows(&self) -> ChunksExact<'_, [u8; 3]> {
+        self.as_slice().chunks_exact(self.width().max(1))
+    }
+
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.frame.pixel_format
+    }
+
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+
+    pub fn into_frame(self) -> Frame {
+        self.frame
+    }
+}
+
+impl TryFrom<Frame> for ColorFrame {
+    type Error = Error;
+
+    fn try_from(frame: Frame) -> ZenseResult<Self> {
+        validate(
+            &frame,
+            &[FrameType::RgbFrame, FrameType::MappedRgbFrame],
+            &[PixelFormat::Rgb888, PixelFormat::Bgr888],
+        )?;
+        Ok(ColorFrame { frame })
+    }
+}
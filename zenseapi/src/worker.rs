@@ -0,0 +1,384 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex, MutexGuard};
+#[cfg(feature = "async")]
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::subscription::Subscribers;
+use crate::{
+    DeviceHandle, Error, Frame, FramePool, FrameSet, FrameType, Subscription, ZenseError,
+    ZenseResult,
+};
+
+/// What `CaptureWorker` does with a capture when its queue is full.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum DropPolicy {
+    /// Waits until the consumer takes a capture. The device may drop frames meanwhile.
+    Block,
+    /// Discards the oldest queued capture.
+    DropOldest,
+    /// Discards the new capture.
+    DropNewest,
+    /// Keeps only the latest capture, whatever the capacity is.
+    KeepLatest,
+}
+
+#[derive(Clone, Debug)]
+pub struct CaptureOptions {
+    pub session_index: u32,
+    /// Maximum number of queued captures. At least 1.
+    pub capacity: usize,
+    pub policy: DropPolicy,
+    /// Pool to take frame buffers from, see `Stream::set_frame_pool()`.
+    pub frame_pool: Option<FramePool>,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        CaptureOptions {
+            session_index: 0,
+            capacity: 4,
+            policy: DropPolicy::Block,
+            frame_pool: None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct CaptureStats {
+    /// Captures read from the device, including failed ones.
+    pub captured: u64,
+    /// Captures discarded by the `DropPolicy`.
+    pub dropped_by_policy: u64,
+    /// Frames the device skipped, estimated from gaps in `Frame::frame_index`.
+    pub dropped_by_device: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    captured: AtomicU64,
+    dropped_by_policy: AtomicU64,
+    dropped_by_device: AtomicU64,
+}
+
+struct Queue {
+    items: VecDeque<ZenseResult<FrameSet>>,
+    finished: bool,
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    shutdown: AtomicBool,
+    counters: Counters,
+    options: CaptureOptions,
+    #[cfg(feature = "async")]
+    waker: Mutex<Option<Waker>>,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn push(&self, item: ZenseResult<FrameSet>) {
+        let capacity = self.options.capacity.max(1);
+        let mut queue = self.lock();
+        match self.options.policy {
+            DropPolicy::Block => {
+                while queue.items.len() >= capacity && !self.shutdown.load(Ordering::Acquire) {
+                    queue = self.not_full.wait(queue).unwrap_or_else(|e| e.into_inner());
+                }
+                if self.shutdown.load(Ordering::Acquire) {
+                    return;
+                }
+            }
+            DropPolicy::DropOldest => {
+                while queue.items.len() >= capacity {
+                    queue.items.pop_front();
+                    self.counters
+                        .dropped_by_policy
+                        .fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            DropPolicy::DropNewest => {
+                if queue.items.len() >= capacity {
+                    self.counters
+                        .dropped_by_policy
+                        .fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+            }
+            DropPolicy::KeepLatest => {
+                let dropped = queue.items.len() as u64;
+                queue.items.clear();
+                self.counters
+                    .dropped_by_policy
+                    .fetch_add(dropped, Ordering::Relaxed);
+            }
+        }
+        queue.items.push_back(item);
+        self.not_empty.notify_one();
+        drop(queue);
+        self.wake();
+    }
+
+    /// Ends the queue, after `error` if there is one, which is queued whatever the policy.
+    fn finish(&self, error: Option<Error>) {
+        let mut queue = self.lock();
+        queue.items.extend(error.map(Err));
+        queue.finished = true;
+        drop(queue);
+        self.not_empty.notify_all();
+        self.wake();
+    }
+
+    #[cfg(feature = "async")]
+    fn wake(&self) {
+        let waker = self.waker.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn wake(&self) {}
+}
+
+/// Captures frames of a device on a dedicated thread and queues them for the consumer.
+///
+/// Failed captures are queued as errors, and the worker waits a little longer after each
+/// consecutive one. An error of class `DeviceLost` ends the worker: it is queued last, after
+/// which `recv()` returns `None`.
+///
+/// The device is moved into the worker, so it must not borrow its context: open it with
+/// `Zense::open_device_shared()`, which keeps the context alive until the device is dropped.
+/// The stream is stopped and the thread joined when this is dropped, or by `stop()`.
+pub struct CaptureWorker {
+    shared: Arc<Shared>,
+    subscribers: Arc<Subscribers>,
+    thread: Option<JoinHandle<DeviceHandle<'static>>>,
+}
+
+impl CaptureWorker {
+    /// Starts the stream of `options.session_index` on a new thread.
+    ///
+    /// Returns once the stream has started, or with the error which prevented it.
+    pub fn spawn(device: DeviceHandle<'static>, options: CaptureOptions) -> ZenseResult<Self> {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                items: VecDeque::with_capacity(options.capacity.max(1)),
+                finished: false,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            shutdown: AtomicBool::new(false),
+            counters: Counters::default(),
+            options,
+            #[cfg(feature = "async")]
+            waker: Mutex::new(None),
+        });
+        let subscribers = Arc::clone(&device.subscribers);
+        let (started_tx, started_rx) = mpsc::channel();
+        let thread_shared = Arc::clone(&shared);
+        let thread = thread::Builder::new()
+            .name(String::from("zense-capture"))
+            .spawn(move || {
+                let error = capture_loop(&device, &thread_shared, started_tx);
+                thread_shared.finish(error);
+                device
+            })
+            .map_err(|_| Error::from(ZenseError::RuntimeError))?;
+        match started_rx.recv() {
+            Ok(Ok(())) => Ok(CaptureWorker {
+                shared,
+                subscribers,
+                thread: Some(thread),
+            }),
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                Err(e)
+            }
+            Err(_) => {
+                let _ = thread.join();
+                Err(ZenseError::RuntimeError.into())
+            }
+        }
+    }
+
+    /// Waits for the next capture. Returns `None` once the worker finished and the queue is empty.
+    pub fn recv(&self) -> Option<ZenseResult<FrameSet>> {
+        let mut queue = self.shared.lock();
+        loop {
+            if let Some(item) = queue.items.pop_front() {
+                self.shared.not_full.notify_one();
+                return Some(item);
+            }
+            if queue.finished {
+                return None;
+            }
+            queue = self
+                .shared
+                .not_empty
+                .wait(queue)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Like `recv()`, but also returns `None` when nothing arrived within `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<ZenseResult<FrameSet>> {
+        let deadline = Instant::now() + timeout;
+        let mut queue = self.shared.lock();
+        loop {
+            if let Some(item) = queue.items.pop_front() {
+                self.shared.not_full.notify_one();
+                return Some(item);
+            }
+            let now = Instant::now();
+            if queue.finished || now >= deadline {
+                return None;
+            }
+            queue = self
+                .shared
+                .not_empty
+                .wait_timeout(queue, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    /// Takes a queued capture without waiting.
+    pub fn try_recv(&self) -> Option<ZenseResult<FrameSet>> {
+        let item = self.shared.lock().items.pop_front();
+        if item.is_some() {
+            self.shared.not_full.notify_one();
+        }
+        item
+    }
+
+    /// Blocking iterator over captures, ending when the worker finished.
+    pub fn iter(&self) -> impl Iterator<Item = ZenseResult<FrameSet>> + '_ {
+        std::iter::from_fn(move || self.recv())
+    }
+
+    /// Takes a queued capture, or registers `cx` to be woken when one arrives.
+    #[cfg(feature = "async")]
+    pub(crate) fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Option<ZenseResult<FrameSet>>> {
+        // registered before checking the queue so that a capture pushed in between wakes us
+        *self.shared.waker.lock().unwrap_or_else(|e| e.into_inner()) = Some(cx.waker().clone());
+        let mut queue = self.shared.lock();
+        match queue.items.pop_front() {
+            Some(item) => {
+                self.shared.not_full.notify_one();
+                Poll::Ready(Some(item))
+            }
+            None if queue.finished => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+
+    /// `DeviceHandle::subscribe()` of the device owned by this worker.
+    pub fn subscribe<F>(&self, frame_type: FrameType, callback: F) -> Subscription
+    where
+        F: FnMut(&Frame) + Send + 'static,
+    {
+        self.subscribers.subscribe(frame_type, callback)
+    }
+
+    pub fn stats(&self) -> CaptureStats {
+        let counters = &self.shared.counters;
+        CaptureStats {
+            captured: counters.captured.load(Ordering::Relaxed),
+            dropped_by_policy: counters.dropped_by_policy.load(Ordering::Relaxed),
+            dropped_by_device: counters.dropped_by_device.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Stops the stream, joins the thread and gives the device back.
+    pub fn stop(mut self) -> ZenseResult<DeviceHandle<'static>> {
+        self.join().ok_or_else(|| ZenseError::RuntimeError.into())
+    }
+
+    fn join(&mut self) -> Option<DeviceHandle<'static>> {
+        self.shared.shutdown.store(true, Ordering::Release);
+        {
+            let _queue = self.shared.lock();
+            self.shared.not_full.notify_all();
+        }
+        self.thread.take().and_then(|thread| thread.join().ok())
+    }
+}
+
+impl Drop for CaptureWorker {
+    fn drop(&mut self) {
+        if self.thread.is_some() && self.join().is_none() {
+            log::error!("Capture thread panicked");
+        }
+    }
+}
+
+/// Longest wait after consecutive failed captures, which also bounds how long `stop()` waits.
+const MAX_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Captures until shut down, or returns the error which lost the device.
+fn capture_loop(
+    device: &DeviceHandle<'static>,
+    shared: &Shared,
+    started: mpsc::Sender<ZenseResult<()>>,
+) -> Option<Error> {
+    let mut session = match device.session(shared.options.session_index) {
+        Ok(session) => session,
+        Err(e) => {
+            let _ = started.send(Err(e));
+            return None;
+        }
+    };
+    let mut stream = match session.start_stream() {
+        Ok(stream) => stream,
+        Err(e) => {
+            let _ = started.send(Err(e));
+            return None;
+        }
+    };
+    if let Some(pool) = &shared.options.frame_pool {
+        stream.set_frame_pool(pool.clone());
+    }
+    let _ = started.send(Ok(()));
+
+    // last frame_index seen for each FrameType, indexed by its discriminant
+    let mut last_frame_index: [Option<u32>; 10] = [None; 10];
+    let mut backoff = Duration::from_millis(0);
+    while !shared.shutdown.load(Ordering::Acquire) {
+        let item = stream.capture();
+        shared.counters.captured.fetch_add(1, Ordering::Relaxed);
+        match &item {
+            Ok(frame_set) => {
+                backoff = Duration::from_millis(0);
+                let mut skipped = 0;
+                for frame in frame_set.iter() {
+                    let last = &mut last_frame_index[frame.frame_type as usize];
+                    if let Some(last) = *last {
+                        if frame.frame_index > last {
+                            skipped = skipped.max(frame.frame_index - last - 1);
+                        }
+                    }
+                    *last = Some(frame.frame_index);
+                }
+                shared
+                    .counters
+                    .dropped_by_device
+                    .fetch_add(u64::from(skipped), Ordering::Relaxed);
+            }
+            Err(e) if e.is_device_lost() => return item.err(),
+            Err(_) => backoff = (backoff * 2).clamp(Duration::from_millis(1), MAX_BACKOFF),
+        }
+        shared.push(item);
+        if backoff > Duration::from_millis(0) {
+            thread::sleep(backoff);
+        }
+    }
+    None
+}
@@ -0,0 +1,39 @@
+use zenseapi::enums::ZenseError;
+use zenseapi::ErrorClass;
+
+#[test]
+fn statuses_round_trip() {
+    for status in (-15..=-1).chain(vec![-255, 251, 252, 253, 254, 255]) {
+        let kind = ZenseError::from_int(status);
+        assert_ne!(kind, ZenseError::Unknown, "status {}", status);
+        assert_eq!(kind.to_int(), Some(status));
+    }
+    assert_eq!(ZenseError::from_int(-100), ZenseError::Unknown);
+    assert_eq!(ZenseError::SessionStreaming.to_int(), None);
+}
+
+#[test]
+fn classes() {
+    assert_eq!(
+        ErrorClass::of(ZenseError::ReadNextFrameError),
+        ErrorClass::Transient
+    );
+    assert_eq!(
+        ErrorClass::of(ZenseError::NoDeviceConnected),
+        ErrorClass::DeviceLost
+    );
+    // asking for a frame type which the data mode does not produce fails every time
+    assert_eq!(
+        ErrorClass::of(ZenseError::InvalidFrameType),
+        ErrorClass::Misuse
+    );
+    assert_eq!(
+        ErrorClass::of(ZenseError::SessionStreaming),
+        ErrorClass::Misuse
+    );
+    assert_eq!(
+        ErrorClass::of(ZenseError::UnknownEnumValue),
+        ErrorClass::Unsupported
+    );
+    assert_eq!(ErrorClass::of(ZenseError::Unknown), ErrorClass::Other);
+}
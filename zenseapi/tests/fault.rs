@@ -0,0 +1,210 @@
+use std::time::{Duration, Instant};
+
+use zenseapi::enums::{DataMode, FrameType, ZenseError};
+use zenseapi::fault::{Fault, FaultBackend, FaultRule, Operation, Trigger};
+use zenseapi::sim::{SimBackend, SimConfig};
+use zenseapi::{Backend, SdkBackend, Zense};
+
+#[test]
+fn stale_device_stays_stale_after_replug() {
+    let backend = FaultBackend::new(SimBackend::default(), 0);
+    let controller = backend.controller();
+    let zense = Zense::with_backend(backend).unwrap();
+    let stale = zense.open_device("sim://dcam710/0").unwrap();
+
+    controller.unplug();
+    assert_eq!(
+        zense.open_device("sim://dcam710/0").unwrap_err(),
+        ZenseError::NoDeviceConnected
+    );
+    controller.replug();
+    // the stale device is still open in the simulator, which refuses to open it twice
+    assert_eq!(
+        zense.open_device("sim://dcam710/0").unwrap_err(),
+        ZenseError::InvalidParams
+    );
+    let e = stale.session(0).unwrap().get_data_mode().unwrap_err();
+    assert_eq!(e, ZenseError::CameraNotOpened);
+    stale.close_device().unwrap();
+
+    let device = zense.open_device("sim://dcam710/0").unwrap();
+    let mut session = device.session(0).unwrap();
+    session.get_data_mode().unwrap();
+    let mut stream = session.start_stream().unwrap();
+    stream.read_next_frame().unwrap();
+    drop(stream);
+    device.close_device().unwrap();
+    assert_eq!(controller.call_count(Operation::CloseDevice), 2);
+}
+
+#[test]
+fn frames_of_a_stale_device_outlive_other_opens() {
+    let backend = FaultBackend::new(
+        SimBackend::new(SimConfig {
+            device_count: 2,
+            ..SimConfig::default()
+        }),
+        0,
+    );
+    let controller = backend.controller();
+    let zense = Zense::with_backend(backend).unwrap();
+    let stale = zense.open_device("sim://dcam710/0").unwrap();
+    let other = {
+        let mut session = stale.session(0).unwrap();
+        session.set_data_mode(DataMode::DepthAndIr30Fps).unwrap();
+        let mut stream = session.start_stream().unwrap();
+        stream.read_next_frame().unwrap();
+        let frame = stream.get_frame_ref(FrameType::DepthFrame).unwrap();
+        let copy = frame.frame_data.to_vec();
+
+        controller.unplug();
+        controller.replug();
+        let other = zense.open_device("sim://dcam710/1").unwrap();
+        // the buffer borrowed before the unplug is still the one which was read
+        assert_eq!(frame.frame_data, &copy[..]);
+        assert!(zense.open_device("sim://dcam710/0").is_err());
+        other
+    };
+    stale.close_device().unwrap();
+    zense.open_device("sim://dcam710/0").unwrap();
+    other.close_device().unwrap();
+}
+
+/// Which of `calls` calls of `get_device_count()` fail under `trigger`.
+fn failing_calls(trigger: Trigger, seed: u64, calls: u64) -> Vec<u64> {
+    let backend = FaultBackend::with_rules(
+        SimBackend::default(),
+        seed,
+        vec![FaultRule::new(
+            Operation::GetDeviceCount,
+            trigger,
+            Fault::Error(ZenseError::ReadNextFrameError),
+        )],
+    );
+    let controller = backend.controller();
+    let zense = Zense::with_backend(backend).unwrap();
+    let failing: Vec<_> = (1..=calls)
+        .filter(|_| zense.get_device_count().is_err())
+        .collect();
+    assert_eq!(controller.call_count(Operation::GetDeviceCount), calls);
+    assert_eq!(controller.injected_count(), failing.len() as u64);
+    failing
+}
+
+#[test]
+fn triggers_select_calls() {
+    assert_eq!(failing_calls(Trigger::Always, 0, 3), vec![1, 2, 3]);
+    assert_eq!(failing_calls(Trigger::Nth(2), 0, 5), vec![2]);
+    assert_eq!(failing_calls(Trigger::From(4), 0, 5), vec![4, 5]);
+    assert_eq!(failing_calls(Trigger::Every(3), 0, 7), vec![3, 6]);
+    assert!(failing_calls(Trigger::Every(0), 0, 3).is_empty());
+    assert!(failing_calls(Trigger::Probability(0.0), 0, 20).is_empty());
+    assert_eq!(failing_calls(Trigger::Probability(1.0), 0, 20).len(), 20);
+
+    let pattern = failing_calls(Trigger::Probability(0.5), 7, 64);
+    assert_eq!(failing_calls(Trigger::Probability(0.5), 7, 64), pattern);
+    assert!(!pattern.is_empty() && pattern.len() < 64);
+}
+
+#[test]
+fn injected_errors_and_stalls() {
+    let backend = FaultBackend::new(SimBackend::default(), 0);
+    let controller = backend.controller();
+    let zense = Zense::with_backend(backend).unwrap();
+
+    controller.inject(FaultRule::new(
+        Operation::GetDeviceCount,
+        Trigger::Always,
+        Fault::Error(ZenseError::InvalidParams),
+    ));
+    let e = zense.get_device_count().unwrap_err();
+    assert_eq!(e, ZenseError::InvalidParams);
+    assert_eq!(e.function(), Some("Ps2_GetDeviceCount"));
+    controller.clear();
+    assert_eq!(zense.get_device_count().unwrap(), 1);
+
+    let stall = Duration::from_millis(50);
+    controller.inject(FaultRule::new(
+        Operation::GetDeviceCount,
+        Trigger::Nth(3),
+        Fault::Stall(stall),
+    ));
+    let start = Instant::now();
+    assert_eq!(zense.get_device_count().unwrap(), 1);
+    assert!(start.elapsed() >= stall);
+}
+
+#[test]
+fn unplug_and_replug() {
+    let backend = FaultBackend::new(SimBackend::default(), 0);
+    let controller = backend.controller();
+    let zense = Zense::with_backend(backend).unwrap();
+    assert!(controller.is_plugged());
+
+    controller.unplug();
+    assert!(!controller.is_plugged());
+    assert_eq!(zense.get_device_count().unwrap(), 0);
+    controller.replug();
+    assert_eq!(zense.get_device_count().unwrap(), 1);
+
+    // the same through faults injected into the calls
+    let device = zense.open_device("sim://dcam710/0").unwrap();
+    controller.inject(FaultRule::new(
+        Operation::ReadNextFrame,
+        Trigger::Nth(1),
+        Fault::Unplug,
+    ));
+    controller.inject(FaultRule::new(
+        Operation::GetDeviceCount,
+        Trigger::From(3),
+        Fault::Replug,
+    ));
+    {
+        let mut session = device.session(0).unwrap();
+        let mut stream = session.start_stream().unwrap();
+        let e = stream.read_next_frame().unwrap_err();
+        assert_eq!(e, ZenseError::NoDeviceConnected);
+        assert!(e.is_device_lost());
+    }
+    assert!(!controller.is_plugged());
+    assert_eq!(zense.get_device_count().unwrap(), 1);
+    assert!(controller.is_plugged());
+    assert_eq!(
+        device.session(0).unwrap().get_data_mode().unwrap_err(),
+        ZenseError::CameraNotOpened
+    );
+}
+
+#[test]
+fn dropped_frames_leave_a_gap() {
+    let backend = FaultBackend::with_rules(
+        SimBackend::default(),
+        0,
+        vec![FaultRule::new(
+            Operation::ReadNextFrame,
+            Trigger::Nth(2),
+            Fault::DropFrames(2),
+        )],
+    );
+    let zense = Zense::with_backend(backend).unwrap();
+    let device = zense.open_device("sim://dcam710/0").unwrap();
+    let mut session = device.session(0).unwrap();
+    session.set_data_mode(DataMode::DepthAndIr30Fps).unwrap();
+    let mut stream = session.start_stream().unwrap();
+    let mut depth_index = || {
+        let frame_set = stream.capture().unwrap();
+        frame_set.depth.unwrap().frame_index
+    };
+    let first = depth_index();
+    assert_eq!(depth_index(), first + 3);
+}
+
+#[test]
+fn exclusivity_is_passed_through() {
+    assert!(SdkBackend.exclusive());
+    assert!(FaultBackend::new(SdkBackend, 0).exclusive());
+    assert!(!FaultBackend::new(SimBackend::default(), 0).exclusive());
+    // contexts of the simulator do not hold the SDK
+    let _a = Zense::with_backend(SimBackend::default()).unwrap();
+    let _b = Zense::with_backend(SimBackend::default()).unwrap();
+}
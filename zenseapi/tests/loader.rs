@@ -0,0 +1,23 @@
+#![cfg(feature = "runtime-loading")]
+
+use zenseapi::enums::ZenseError;
+use zenseapi::sys::loader;
+use zenseapi::Zense;
+
+#[test]
+fn failed_load_is_reported_and_not_retried() {
+    std::env::set_var(loader::SDK_PATH_VAR, "/nonexistent/libvzense_api.so");
+    for _ in 0..2 {
+        let e = Zense::new().unwrap_err();
+        assert_eq!(e, ZenseError::SdkNotAvailable);
+        let detail = e.detail().unwrap();
+        assert!(
+            detail.contains("/nonexistent/libvzense_api.so"),
+            "{}",
+            detail
+        );
+        assert!(e.to_string().ends_with(detail));
+    }
+    assert!(!loader::is_loaded());
+    assert!(loader::load_error().is_some());
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "async")]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use futures_core::Stream;
+use zenseapi::nonblocking::{self, FrameStream};
+use zenseapi::sim::SimBackend;
+use zenseapi::{CaptureOptions, Zense};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+fn next<S: Stream + Unpin>(stream: &mut S) -> impl Future<Output = Option<S::Item>> + '_ {
+    std::future::poll_fn(move |cx| Pin::new(&mut *stream).poll_next(cx))
+}
+
+#[test]
+fn frame_stream_with_shared_context() {
+    let zense = Arc::new(Zense::with_backend(SimBackend::default()).unwrap());
+    block_on(async {
+        let device = nonblocking::open_device(&zense, "sim://dcam710/0")
+            .await
+            .unwrap();
+        let mut frames = FrameStream::start(device, CaptureOptions::default())
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            assert!(next(&mut frames).await.unwrap().unwrap().depth.is_some());
+        }
+        let device = frames.stop().await.unwrap();
+        nonblocking::close_device(device).await.unwrap();
+    });
+    assert_eq!(Arc::strong_count(&zense), 1);
+}
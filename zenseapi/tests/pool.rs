@@ -0,0 +1,60 @@
+use zenseapi::enums::FrameType;
+use zenseapi::{FramePool, FramePoolStats};
+
+#[test]
+fn buffers_are_reused() {
+    let pool = FramePool::new(2);
+    let a = pool.acquire(FrameType::DepthFrame, 4, 2, 16);
+    let b = pool.acquire(FrameType::DepthFrame, 4, 2, 16);
+    drop(a);
+    drop(b);
+    let c = pool.acquire(FrameType::DepthFrame, 4, 2, 16);
+    // another resolution does not take buffers of this one
+    let d = pool.acquire(FrameType::DepthFrame, 2, 2, 8);
+    assert!(c.is_pooled());
+    assert_eq!(c.len(), 16);
+    assert_eq!(
+        pool.stats(),
+        FramePoolStats {
+            allocated: 3,
+            reused: 1,
+            in_use: 2,
+            idle: 1,
+            high_water: 2,
+        }
+    );
+    drop((c, d));
+    pool.clear();
+    assert_eq!(pool.stats().idle, 0);
+    assert_eq!(pool.stats().in_use, 0);
+}
+
+#[test]
+fn idle_buffers_are_capped() {
+    let pool = FramePool::new(1);
+    let buffers: Vec<_> = (0..3)
+        .map(|_| pool.acquire(FrameType::IrFrame, 4, 2, 8))
+        .collect();
+    drop(buffers);
+    let stats = pool.stats();
+    assert_eq!((stats.in_use, stats.idle, stats.high_water), (0, 1, 3));
+}
+
+#[test]
+fn into_vec_leaves_the_pool() {
+    let pool = FramePool::new(2);
+    let a = pool.acquire(FrameType::RgbFrame, 2, 2, 12);
+    let b = pool.acquire(FrameType::RgbFrame, 2, 2, 12);
+    let data = a.into_vec();
+    assert_eq!(data.len(), 12);
+    assert_eq!(pool.stats().in_use, 1);
+    drop(b);
+    drop(data);
+    let stats = pool.stats();
+    assert_eq!((stats.in_use, stats.idle), (0, 1));
+
+    // a buffer outliving its pool is not counted anywhere
+    let c = pool.acquire(FrameType::RgbFrame, 2, 2, 12);
+    drop(pool);
+    assert_eq!(c.into_vec().len(), 12);
+}
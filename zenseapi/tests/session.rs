@@ -0,0 +1,97 @@
+use zenseapi::enums::{DataMode, FrameType, PropertyType, PropertyValue, ZenseError};
+use zenseapi::fault::{FaultBackend, Operation};
+use zenseapi::sim::SimBackend;
+use zenseapi::Zense;
+
+fn sim() -> Zense {
+    Zense::with_backend(SimBackend::default()).unwrap()
+}
+
+#[test]
+fn second_session_cannot_restart_a_streaming_index() {
+    let zense = sim();
+    let device = zense.open_device("sim://dcam710/0").unwrap();
+    let mut a = device.session(0).unwrap();
+    let mut s1 = a.start_stream().unwrap();
+    s1.read_next_frame().unwrap();
+    let frame = s1.get_frame_ref(FrameType::DepthFrame).unwrap();
+
+    let mut b = device.session(0).unwrap();
+    let e = b.set_data_mode(DataMode::DepthAndIr30Fps).unwrap_err();
+    assert_eq!(e, ZenseError::SessionStreaming);
+    let e = b.start_stream().unwrap_err();
+    assert_eq!(e, ZenseError::SessionStreaming);
+    assert_eq!(e.session_index(), Some(0));
+
+    // the borrowed buffer is still the one read by s1
+    assert_eq!(frame.frame_data.len(), 640 * 480 * 2);
+    drop(s1);
+
+    b.set_data_mode(DataMode::DepthAndIr30Fps).unwrap();
+    let s2 = b.start_stream().unwrap();
+    s2.stop().unwrap();
+    assert!(b.start_stream().is_ok());
+}
+
+#[test]
+fn data_mode_property_is_typed() {
+    let zense = sim();
+    let device = zense.open_device("sim://dcam710/0").unwrap();
+    let session = device.session(0).unwrap();
+    assert_eq!(
+        session.get_property(PropertyType::DataMode).unwrap(),
+        PropertyValue::DataModeValue(DataMode::DepthAndRgb30Fps)
+    );
+    session
+        .set_property_verified(
+            PropertyType::DataMode,
+            PropertyValue::DataModeValue(DataMode::WdrDepth),
+        )
+        .unwrap();
+    assert_eq!(session.get_data_mode().unwrap(), DataMode::WdrDepth);
+
+    let e = session
+        .set_property(PropertyType::DataMode, PropertyValue::Uint8Value(11))
+        .unwrap_err();
+    assert_eq!(e, ZenseError::InvalidParams);
+    assert!(session.supported_data_modes().unwrap().len() > 1);
+}
+
+#[test]
+fn session_index_is_checked() {
+    let zense = sim();
+    let device = zense.open_device("sim://dcam710/0").unwrap();
+    assert_eq!(device.session_count(), 1);
+    let e = device.session(1).unwrap_err();
+    assert_eq!(e, ZenseError::InvalidSessionIndex);
+    assert_eq!(e.device(), Some("sim://dcam710/0"));
+}
+
+#[test]
+fn stream_lifecycle() {
+    let backend = FaultBackend::new(SimBackend::default(), 0);
+    let controller = backend.controller();
+    let zense = Zense::with_backend(backend).unwrap();
+    let device = zense.open_device("sim://dcam710/0").unwrap();
+    {
+        let mut session = device.session(0).unwrap();
+        session.set_data_mode(DataMode::DepthAndIr30Fps).unwrap();
+
+        let mut stream = session.start_stream().unwrap();
+        assert_eq!(stream.get_data_mode().unwrap(), DataMode::DepthAndIr30Fps);
+        assert!(stream.read_next_frame().unwrap().depth);
+        stream.stop().unwrap();
+        assert_eq!(controller.call_count(Operation::StopStream), 1);
+
+        // dropping stops as well
+        let mut stream = session.start_stream().unwrap();
+        stream.read_next_frame().unwrap();
+        drop(stream);
+        assert_eq!(controller.call_count(Operation::StartStream), 2);
+        assert_eq!(controller.call_count(Operation::StopStream), 2);
+    }
+    device.close_device().unwrap();
+    assert_eq!(controller.call_count(Operation::CloseDevice), 1);
+    drop(zense);
+    assert_eq!(controller.call_count(Operation::Shutdown), 1);
+}
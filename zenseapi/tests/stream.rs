@@ -0,0 +1,140 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use zenseapi::enums::{DataMode, FrameType, ZenseError};
+use zenseapi::fault::{Fault, FaultBackend, FaultRule, Operation, Trigger};
+use zenseapi::sim::SimBackend;
+use zenseapi::{DeviceHandle, Zense};
+
+#[test]
+fn frames_end_after_device_lost() {
+    let backend = FaultBackend::with_rules(
+        SimBackend::default(),
+        0,
+        vec![
+            FaultRule::new(
+                Operation::ReadNextFrame,
+                Trigger::Nth(1),
+                Fault::Error(ZenseError::ReadNextFrameError),
+            ),
+            FaultRule::new(Operation::ReadNextFrame, Trigger::Nth(3), Fault::Unplug),
+        ],
+    );
+    let zense = Zense::with_backend(backend).unwrap();
+    let device = zense.open_device("sim://dcam710/0").unwrap();
+    let mut session = device.session(0).unwrap();
+    session.set_data_mode(DataMode::DepthAndIr30Fps).unwrap();
+    let mut stream = session.start_stream().unwrap();
+    let items: Vec<_> = stream.frames().of_type(FrameType::DepthFrame).collect();
+    assert_eq!(items.len(), 3);
+    assert_eq!(
+        items[0].as_ref().unwrap_err(),
+        &ZenseError::ReadNextFrameError
+    );
+    assert!(items[1].is_ok());
+    assert_eq!(
+        items[2].as_ref().unwrap_err(),
+        &ZenseError::NoDeviceConnected
+    );
+}
+
+fn sim_device(zense: &Zense) -> DeviceHandle<'_> {
+    let device = zense.open_device("sim://dcam710/0").unwrap();
+    device
+        .session(0)
+        .unwrap()
+        .set_data_mode(DataMode::DepthAndIr30Fps)
+        .unwrap();
+    device
+}
+
+#[test]
+fn capture_gathers_ready_frames() {
+    let zense = Zense::with_backend(SimBackend::default()).unwrap();
+    let device = sim_device(&zense);
+    let mut session = device.session(0).unwrap();
+    let mut stream = session.start_stream().unwrap();
+
+    let first = stream.capture().unwrap();
+    let second = stream.capture().unwrap();
+    assert_eq!((first.sequence, second.sequence), (0, 1));
+    let depth = second.get(FrameType::DepthFrame).unwrap();
+    assert_eq!((depth.width, depth.height), (640, 480));
+    assert_eq!(depth.frame_data.len(), 640 * 480 * 2);
+    assert_eq!(
+        depth.frame_index,
+        first.depth.as_ref().unwrap().frame_index + 1
+    );
+    assert!(second.ir.is_some() && second.confidence.is_some());
+    assert!(second.rgb.is_none());
+
+    let mut retained = second.clone();
+    retained.retain(&[FrameType::IrFrame]);
+    assert!(retained.depth.is_none() && retained.ir.is_some());
+    assert_eq!(retained.sequence, 1);
+}
+
+#[test]
+fn subscribers_see_captures() {
+    let zense = Zense::with_backend(SimBackend::default()).unwrap();
+    let device = sim_device(&zense);
+    let count = Arc::new(AtomicUsize::new(0));
+    let subscription = {
+        let count = Arc::clone(&count);
+        device.subscribe(FrameType::IrFrame, move |frame| {
+            assert_eq!(frame.frame_type, FrameType::IrFrame);
+            count.fetch_add(1, Ordering::Relaxed);
+        })
+    };
+    let mut session = device.session(0).unwrap();
+    let mut stream = session.start_stream().unwrap();
+    stream.capture().unwrap();
+    stream.capture().unwrap();
+    subscription.unsubscribe();
+    stream.capture().unwrap();
+    assert_eq!(count.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn frames_end_on_limit_and_stop_request() {
+    let zense = Zense::with_backend(SimBackend::default()).unwrap();
+    let device = sim_device(&zense);
+    let mut session = device.session(0).unwrap();
+    let mut stream = session.start_stream().unwrap();
+    let sequences: Vec<_> = stream
+        .frames()
+        .limit(3)
+        .map(|frame_set| frame_set.unwrap().sequence)
+        .collect();
+    assert_eq!(sequences, vec![0, 1, 2]);
+
+    let stop_handle = stream.stop_handle();
+    let mut frames = stream.frames();
+    assert!(frames.next().is_some());
+    stop_handle.stop();
+    assert!(frames.next().is_none());
+    // the stream was stopped by the iterator
+    assert!(stream.read_next_frame().is_err());
+}
+
+#[test]
+fn corrupt_frame_length_is_rejected() {
+    let backend = FaultBackend::with_rules(
+        SimBackend::default(),
+        0,
+        vec![FaultRule::new(
+            Operation::GetFrame,
+            Trigger::Always,
+            Fault::CorruptDataLen(640 * 480 * 4),
+        )],
+    );
+    let zense = Zense::with_backend(backend).unwrap();
+    let device = sim_device(&zense);
+    let mut session = device.session(0).unwrap();
+    let mut stream = session.start_stream().unwrap();
+    stream.read_next_frame().unwrap();
+    let e = stream.get_frame_ref(FrameType::DepthFrame).unwrap_err();
+    assert_eq!(e, ZenseError::InvalidFrameSize);
+    assert_eq!(e.function(), Some("Ps2_GetFrame"));
+    assert_eq!(stream.capture().unwrap_err(), ZenseError::InvalidFrameSize);
+}
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use zenseapi::enums::{DataMode, ZenseError};
+use zenseapi::fault::{Fault, FaultBackend, FaultRule, Operation, Trigger};
+use zenseapi::sim::SimBackend;
+use zenseapi::{
+    CaptureOptions, CaptureWorker, DropPolicy, ErrorClass, FramePool, FrameSet, Zense, ZenseResult,
+};
+
+#[test]
+fn worker_owns_a_shared_context() {
+    let zense = Arc::new(Zense::with_backend(SimBackend::default()).unwrap());
+    let device = Zense::open_device_shared(&zense, "sim://dcam710/0").unwrap();
+    let worker = CaptureWorker::spawn(device, CaptureOptions::default()).unwrap();
+    let frame_set = worker.recv().unwrap().unwrap();
+    assert!(frame_set.depth.is_some());
+    let device = worker.stop().unwrap();
+    assert_eq!(Arc::strong_count(&zense), 2);
+    drop(device);
+    assert_eq!(Arc::strong_count(&zense), 1);
+    Arc::try_unwrap(zense).unwrap().shutdown().unwrap();
+}
+
+#[test]
+fn worker_ends_on_unplug() {
+    for &policy in &[
+        DropPolicy::Block,
+        DropPolicy::DropOldest,
+        DropPolicy::DropNewest,
+        DropPolicy::KeepLatest,
+    ] {
+        let backend = FaultBackend::with_rules(
+            SimBackend::default(),
+            0,
+            vec![FaultRule::new(
+                Operation::ReadNextFrame,
+                Trigger::Nth(3),
+                Fault::Unplug,
+            )],
+        );
+        let zense = Arc::new(Zense::with_backend(backend).unwrap());
+        let device = Zense::open_device_shared(&zense, "sim://dcam710/0").unwrap();
+        // no RGB to render, which keeps the test quick
+        device
+            .session(0)
+            .unwrap()
+            .set_data_mode(DataMode::DepthAndIr30Fps)
+            .unwrap();
+        let options = CaptureOptions {
+            capacity: 2,
+            policy,
+            ..CaptureOptions::default()
+        };
+        let worker = CaptureWorker::spawn(device, options).unwrap();
+        let last = worker
+            .iter()
+            .last()
+            .expect("the worker queues the error which ended it");
+        let e = last.unwrap_err();
+        assert_eq!(e, ZenseError::NoDeviceConnected);
+        assert_eq!(e.class(), ErrorClass::DeviceLost);
+        assert!(worker.recv_timeout(Duration::from_millis(10)).is_none());
+        assert_eq!(worker.stats().captured, 3);
+    }
+}
+
+fn spawn(policy: DropPolicy, capacity: usize, frame_pool: Option<FramePool>) -> CaptureWorker {
+    let zense = Arc::new(Zense::with_backend(SimBackend::default()).unwrap());
+    let device = Zense::open_device_shared(&zense, "sim://dcam710/0").unwrap();
+    device
+        .session(0)
+        .unwrap()
+        .set_data_mode(DataMode::DepthAndIr30Fps)
+        .unwrap();
+    let options = CaptureOptions {
+        capacity,
+        policy,
+        frame_pool,
+        ..CaptureOptions::default()
+    };
+    CaptureWorker::spawn(device, options).unwrap()
+}
+
+/// Waits, without consuming anything, until the worker captured `count` times.
+fn wait_for(worker: &CaptureWorker, count: u64) {
+    let deadline = Instant::now() + Duration::from_secs(60);
+    while worker.stats().captured < count {
+        assert!(Instant::now() < deadline, "the worker stalled");
+        thread::sleep(Duration::from_millis(5));
+    }
+}
+
+fn sequence(item: Option<ZenseResult<FrameSet>>) -> u64 {
+    item.unwrap().unwrap().sequence
+}
+
+#[test]
+fn block_loses_nothing() {
+    let worker = spawn(DropPolicy::Block, 2, None);
+    wait_for(&worker, 3);
+    let sequences: Vec<_> = (0..5).map(|_| sequence(worker.recv())).collect();
+    assert_eq!(sequences, vec![0, 1, 2, 3, 4]);
+    assert_eq!(worker.stats().dropped_by_policy, 0);
+    worker.stop().unwrap();
+}
+
+#[test]
+fn drop_newest_keeps_the_first_captures() {
+    let worker = spawn(DropPolicy::DropNewest, 2, None);
+    wait_for(&worker, 5);
+    assert_eq!(sequence(worker.try_recv()), 0);
+    assert_eq!(sequence(worker.try_recv()), 1);
+    assert!(worker.stats().dropped_by_policy >= 3);
+}
+
+#[test]
+fn drop_oldest_keeps_the_last_captures() {
+    let worker = spawn(DropPolicy::DropOldest, 2, None);
+    wait_for(&worker, 5);
+    let first = sequence(worker.try_recv());
+    assert!(first >= 3);
+    assert!(sequence(worker.try_recv()) > first);
+    assert!(worker.stats().dropped_by_policy >= 3);
+}
+
+#[test]
+fn keep_latest_queues_one_capture() {
+    let worker = spawn(DropPolicy::KeepLatest, 4, None);
+    wait_for(&worker, 4);
+    assert!(sequence(worker.try_recv()) >= 3);
+    assert!(worker
+        .try_recv()
+        .map_or(true, |item| item.unwrap().sequence >= 4));
+    assert!(worker.stats().dropped_by_policy >= 3);
+}
+
+#[test]
+fn worker_takes_buffers_from_the_pool() {
+    let pool = FramePool::new(4);
+    let worker = spawn(DropPolicy::Block, 1, Some(pool.clone()));
+    for _ in 0..4 {
+        drop(worker.recv().unwrap().unwrap());
+    }
+    drop(worker);
+    let stats = pool.stats();
+    assert!(stats.reused > 0);
+    assert_eq!(stats.in_use, 0);
+}